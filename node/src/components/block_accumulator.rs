@@ -395,6 +395,10 @@ impl BlockAccumulator {
         }
 
         debug!(%finality_signature, "registering finality signature");
+        // The acceptor takes ownership of the signature, so look up its era's weights first.
+        let maybe_evw = self
+            .validator_matrix
+            .weights_for_signature(&finality_signature);
         match acceptor.register_finality_signature(finality_signature, sender, self.validator_slots)
         {
             Ok(Some(finality_signature)) => self.store_block_and_finality_signatures(
@@ -402,7 +406,7 @@ impl BlockAccumulator {
                 ShouldStore::SingleSignature(finality_signature),
                 None,
             ),
-            Ok(None) => match self.validator_matrix.validator_weights(era_id) {
+            Ok(None) => match maybe_evw {
                 Some(evw) => {
                     let (should_store, faulty_senders) = acceptor.should_store_block(&evw);
                     self.store_block_and_finality_signatures(
@@ -245,7 +245,8 @@ impl BlockAcceptor {
         }
 
         let faulty_senders = self.remove_bogus_validators(era_validator_weights);
-        let signature_weight = era_validator_weights.signature_weight(self.signatures.keys());
+        let signature_weight = era_validator_weights
+            .finality_signature_weight(self.signatures.values().map(|(signature, _)| signature));
         if SignatureWeight::Strict == signature_weight {
             self.touch();
             if let Some(meta_block) = self.meta_block.as_mut() {
@@ -22,6 +22,7 @@ use reactor::ReactorEvent;
 
 use crate::{
     components::{
+        block_accumulator::error::{AcceptorError, Bogusness},
         consensus::tests::utils::{
             ALICE_NODE_ID, ALICE_PUBLIC_KEY, ALICE_SECRET_KEY, BOB_NODE_ID, BOB_PUBLIC_KEY,
             BOB_SECRET_KEY, CAROL_PUBLIC_KEY, CAROL_SECRET_KEY,
@@ -766,6 +767,71 @@ fn acceptor_should_store_block() {
     assert_eq!(should_store, ShouldStore::Nothing);
 }
 
+#[test]
+fn acceptor_should_store_block_only_counts_signatures_from_the_block_era() {
+    let mut rng = TestRng::new();
+    let block = Arc::new(Block::random(&mut rng));
+    let era_id = block.header().era_id();
+    let other_era_id = era_id.successor();
+    let mut acceptor = BlockAcceptor::new(*block.hash(), vec![]);
+
+    // Two validators with the same weights in the block's era and in the next one.
+    let keys: Vec<(SecretKey, PublicKey)> = (0..2).map(|_| generate_ed25519_keypair()).collect();
+    let weights: BTreeMap<_, _> = keys
+        .iter()
+        .map(|(_, public_key)| (public_key.clone(), U512::from(50)))
+        .collect();
+    let era_validator_weights = EraValidatorWeights::new(era_id, weights.clone(), Ratio::new(1, 3));
+    let other_era_validator_weights =
+        EraValidatorWeights::new(other_era_id, weights, Ratio::new(1, 3));
+
+    // The first validator signs the block, but claims the signature is from the next era.
+    let faulty_peer = NodeId::random(&mut rng);
+    let fin_sig =
+        FinalitySignature::create(*block.hash(), other_era_id, &keys[0].0, keys[0].1.clone());
+    acceptor
+        .register_finality_signature(fin_sig, Some(faulty_peer), VALIDATOR_SLOTS)
+        .unwrap();
+    let fin_sig = FinalitySignature::create(*block.hash(), era_id, &keys[1].0, keys[1].1.clone());
+    acceptor
+        .register_finality_signature(fin_sig, None, VALIDATOR_SLOTS)
+        .unwrap();
+    acceptor
+        .register_block(meta_block_with_default_state(block.clone()), None)
+        .unwrap();
+
+    // The mismatched signature is removed and its sender is reported. One signature alone is not
+    // enough for strict finality.
+    let (should_store, offenders) = acceptor.should_store_block(&era_validator_weights);
+    assert_eq!(should_store, ShouldStore::Nothing);
+    assert_eq!(1, offenders.len());
+    assert_eq!(faulty_peer, offenders[0].0);
+    assert!(matches!(
+        offenders[0].1,
+        AcceptorError::BogusValidator(Bogusness::SignatureEraIdMismatch)
+    ));
+    assert_eq!(1, acceptor.signatures().len());
+
+    // Now the first validator signs in the block's era.
+    let fin_sig = FinalitySignature::create(*block.hash(), era_id, &keys[0].0, keys[0].1.clone());
+    acceptor
+        .register_finality_signature(fin_sig, None, VALIDATOR_SLOTS)
+        .unwrap();
+
+    // Measured against the next era's weights, both signers are validators with all the weight,
+    // but their signatures are from a different era and must not count.
+    let (should_store, offenders) = acceptor.should_store_block(&other_era_validator_weights);
+    assert_eq!(should_store, ShouldStore::Nothing);
+    assert!(offenders.is_empty());
+
+    // Measured against the block era's weights, the block has strict finality.
+    let (should_store, _offenders) = acceptor.should_store_block(&era_validator_weights);
+    assert!(matches!(
+        should_store,
+        ShouldStore::SufficientlySignedBlock { .. }
+    ));
+}
+
 #[test]
 fn acceptor_should_correctly_bound_the_signatures() {
     let mut rng = TestRng::new();
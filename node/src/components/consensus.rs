@@ -39,7 +39,7 @@ use crate::{
             ConsensusAnnouncement, FatalAnnouncement, MetaBlockAnnouncement,
             PeerBehaviorAnnouncement,
         },
-        diagnostics_port::DumpConsensusStateRequest,
+        diagnostics_port::{ConsensusControlRequest, DumpConsensusStateRequest},
         incoming::{ConsensusDemand, ConsensusMessageIncoming},
         requests::{
             BlockValidationRequest, ChainspecRawBytesRequest, ConsensusRequest,
@@ -177,6 +177,9 @@ pub(crate) enum Event {
     /// Dump state for debugging purposes.
     #[from]
     DumpState(DumpConsensusStateRequest),
+    /// Apply an operator's control command to an era.
+    #[from]
+    Control(ConsensusControlRequest),
 }
 
 impl Debug for ConsensusMessage {
@@ -294,6 +297,7 @@ impl Display for Event {
                 era_id, faulty_num
             ),
             Event::DumpState(req) => Display::fmt(req, f),
+            Event::Control(req) => Display::fmt(req, f),
         }
     }
 }
@@ -486,6 +490,7 @@ where
                     Err(err) => req.answer(Err(err)).ignore(),
                 }
             }
+            Event::Control(req) => self.handle_control_request(effect_builder, rng, req),
         }
     }
 
@@ -7,12 +7,13 @@ use std::{
 
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 
 use casper_hashing::Digest;
 use casper_types::{bytesrepr::ToBytes, TimeDiff, Timestamp};
 
 use crate::{
-    components::consensus::{traits::Context, ActionId, TimerId},
+    components::consensus::{traits::Context, utils::Weight, ActionId, TimerId},
     types::NodeId,
     NodeRng,
 };
@@ -233,6 +234,84 @@ pub(crate) enum ProtocolOutcome<C: Context> {
     HandledProposedBlock(ProposedBlock<C>),
 }
 
+impl<C: Context> ProtocolOutcome<C> {
+    /// Returns a compact JSON summary of this outcome, for debugging traces.
+    ///
+    /// Payloads such as serialized messages and consensus values are omitted; messages are only
+    /// represented by their size in bytes.
+    pub(crate) fn describe(&self) -> serde_json::Value {
+        match self {
+            ProtocolOutcome::CreatedGossipMessage(msg) => json!({
+                "kind": "CreatedGossipMessage",
+                "size": msg.as_raw().len(),
+            }),
+            ProtocolOutcome::CreatedTargetedMessage(msg, peer) => json!({
+                "kind": "CreatedTargetedMessage",
+                "size": msg.as_raw().len(),
+                "peer": peer.to_string(),
+            }),
+            ProtocolOutcome::CreatedMessageToRandomPeer(msg) => json!({
+                "kind": "CreatedMessageToRandomPeer",
+                "size": msg.as_raw().len(),
+            }),
+            ProtocolOutcome::CreatedRequestToRandomPeer(msg) => json!({
+                "kind": "CreatedRequestToRandomPeer",
+                "size": msg.as_raw().len(),
+            }),
+            ProtocolOutcome::ScheduleTimer(timestamp, timer_id) => json!({
+                "kind": "ScheduleTimer",
+                "timestamp": timestamp.millis(),
+                "timer_id": timer_id.0,
+            }),
+            ProtocolOutcome::QueueAction(action_id) => json!({
+                "kind": "QueueAction",
+                "action_id": action_id.0,
+            }),
+            ProtocolOutcome::CreateNewBlock(block_context) => json!({
+                "kind": "CreateNewBlock",
+                "timestamp": block_context.timestamp().millis(),
+                "ancestors": block_context.ancestor_values().len(),
+            }),
+            ProtocolOutcome::FinalizedBlock(finalized_block) => json!({
+                "kind": "FinalizedBlock",
+                "height": finalized_block.relative_height,
+                "timestamp": finalized_block.timestamp.millis(),
+                "proposer": finalized_block.proposer.to_string(),
+                "equivocators": finalized_block.equivocators.len(),
+                "terminal": finalized_block.terminal_block_data.is_some(),
+            }),
+            ProtocolOutcome::ValidateConsensusValue {
+                sender,
+                proposed_block,
+            } => json!({
+                "kind": "ValidateConsensusValue",
+                "sender": sender.to_string(),
+                "timestamp": proposed_block.context().timestamp().millis(),
+            }),
+            ProtocolOutcome::NewEvidence(validator_id) => json!({
+                "kind": "NewEvidence",
+                "validator": validator_id.to_string(),
+            }),
+            ProtocolOutcome::SendEvidence(peer, validator_id) => json!({
+                "kind": "SendEvidence",
+                "peer": peer.to_string(),
+                "validator": validator_id.to_string(),
+            }),
+            ProtocolOutcome::WeAreFaulty => json!({ "kind": "WeAreFaulty" }),
+            ProtocolOutcome::DoppelgangerDetected => json!({ "kind": "DoppelgangerDetected" }),
+            ProtocolOutcome::FttExceeded => json!({ "kind": "FttExceeded" }),
+            ProtocolOutcome::Disconnect(peer) => json!({
+                "kind": "Disconnect",
+                "peer": peer.to_string(),
+            }),
+            ProtocolOutcome::HandledProposedBlock(proposed_block) => json!({
+                "kind": "HandledProposedBlock",
+                "timestamp": proposed_block.context().timestamp().millis(),
+            }),
+        }
+    }
+}
+
 /// An API for a single instance of the consensus.
 pub(crate) trait ConsensusProtocol<C: Context>: Send {
     /// Upcasts consensus protocol into `dyn Any`.
@@ -312,6 +391,14 @@ pub(crate) trait ConsensusProtocol<C: Context>: Send {
     /// Sets the pause status: While paused we don't create consensus messages other than pings.
     fn set_paused(&mut self, paused: bool, now: Timestamp) -> ProtocolOutcomes<C>;
 
+    /// Enables or disables the periodic sync requests to random peers. Not every protocol supports
+    /// this.
+    fn set_sync_enabled(&mut self, enabled: bool);
+
+    /// Makes the given round the current one right away, without waiting for the earlier rounds to
+    /// end. Not every protocol supports this.
+    fn force_start_round(&mut self, round_id: u32, now: Timestamp) -> ProtocolOutcomes<C>;
+
     /// Returns the list of all validators that were observed as faulty in this consensus instance.
     fn validators_with_evidence(&self) -> Vec<&C::ValidatorId>;
 
@@ -321,6 +408,60 @@ pub(crate) trait ConsensusProtocol<C: Context>: Send {
     /// Returns the instance ID of this instance.
     fn instance_id(&self) -> &C::InstanceId;
 
+    /// Returns the number of validators in this instance, including faulty ones.
+    fn validator_count(&self) -> usize;
+
+    /// Returns the sum of all validators' weights.
+    fn total_weight(&self) -> Weight;
+
     // TODO: Make this less Highway-specific.
     fn next_round_length(&self) -> Option<TimeDiff>;
 }
+
+#[cfg(test)]
+mod tests {
+    use casper_types::Timestamp;
+    use serde_json::json;
+
+    use crate::components::consensus::{
+        era_supervisor::SerializedMessage, highway_core::state::tests::TestContext, TimerId,
+    };
+
+    use super::{FinalizedBlock, ProtocolOutcome};
+
+    #[test]
+    fn describe_outcomes() {
+        let finalized_block = FinalizedBlock::<TestContext> {
+            value: 7,
+            timestamp: Timestamp::from(1000),
+            relative_height: 5,
+            equivocators: vec![2],
+            terminal_block_data: None,
+            proposer: 1,
+        };
+        assert_eq!(
+            json!({
+                "kind": "FinalizedBlock",
+                "height": 5,
+                "timestamp": 1000,
+                "proposer": "1",
+                "equivocators": 1,
+                "terminal": false,
+            }),
+            ProtocolOutcome::FinalizedBlock(finalized_block).describe()
+        );
+
+        let timer =
+            ProtocolOutcome::<TestContext>::ScheduleTimer(Timestamp::from(2000), TimerId(3));
+        assert_eq!(
+            json!({ "kind": "ScheduleTimer", "timestamp": 2000, "timer_id": 3 }),
+            timer.describe()
+        );
+
+        let msg = SerializedMessage::from_raw(vec![0; 42]);
+        assert_eq!(
+            json!({ "kind": "CreatedGossipMessage", "size": 42 }),
+            ProtocolOutcome::<TestContext>::CreatedGossipMessage(msg).describe()
+        );
+    }
+}
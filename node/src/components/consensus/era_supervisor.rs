@@ -11,6 +11,7 @@ pub(super) mod debug;
 mod era;
 
 use std::{
+    borrow::Cow,
     cmp,
     collections::{BTreeMap, BTreeSet, HashMap},
     convert::TryInto,
@@ -50,6 +51,7 @@ use crate::{
     },
     effect::{
         announcements::FatalAnnouncement,
+        diagnostics_port::{ConsensusControlCommand, ConsensusControlRequest},
         requests::{BlockValidationRequest, ContractRuntimeRequest, StorageRequest},
         AutoClosingResponder, EffectBuilder, EffectExt, Effects, Responder,
     },
@@ -133,6 +135,9 @@ impl EraSupervisor {
         chainspec: Arc<Chainspec>,
         registry: &Registry,
     ) -> Result<Self, Error> {
+        if !config.zug.is_valid() {
+            return Err(anyhow::anyhow!("invalid Zug configuration"));
+        }
         let unit_files_folder = storage_dir.join("unit_files");
         std::fs::create_dir_all(&unit_files_folder)?;
         info!(our_id = %public_signing_key, "EraSupervisor pubkey",);
@@ -852,6 +857,55 @@ impl EraSupervisor {
         }
     }
 
+    /// Applies an operator's control command to the given era, or to the current one if none is
+    /// given. Only Zug eras support control commands.
+    pub(super) fn handle_control_request<REv: ReactorEventT>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        rng: &mut NodeRng,
+        request: ConsensusControlRequest,
+    ) -> Effects<Event> {
+        let ConsensusControlRequest {
+            era_id,
+            command,
+            responder,
+        } = request;
+        let era_id = match era_id.or_else(|| self.current_era()) {
+            Some(era_id) => era_id,
+            None => {
+                let err = Cow::Borrowed("consensus not initialized");
+                return responder.respond(Err(err)).ignore();
+            }
+        };
+        match self.open_eras.get(&era_id) {
+            None => {
+                let err = Cow::Owned(format!("{} not found", era_id));
+                return responder.respond(Err(err)).ignore();
+            }
+            Some(era) if !era.consensus.as_any().is::<Zug<ClContext>>() => {
+                let err = Cow::Owned(format!("{} does not use Zug", era_id));
+                return responder.respond(Err(err)).ignore();
+            }
+            Some(_) => {}
+        }
+        info!(era = era_id.value(), %command, "applying consensus control command");
+        let mut effects = match command {
+            ConsensusControlCommand::SetSyncEnabled(enabled) => {
+                self.delegate_to_era(effect_builder, rng, era_id, |consensus, _| {
+                    consensus.set_sync_enabled(enabled);
+                    vec![]
+                })
+            }
+            ConsensusControlCommand::ForceStartRound(round_id) => {
+                self.delegate_to_era(effect_builder, rng, era_id, |consensus, _| {
+                    consensus.force_start_round(round_id, Timestamp::now())
+                })
+            }
+        };
+        effects.extend(responder.respond(Ok(())).ignore());
+        effects
+    }
+
     /// Will deactivate voting for the current era.
     /// Does nothing if the current era doesn't exist or is inactive already.
     pub(crate) fn deactivate_current_era(&mut self) -> Result<EraId, String> {
@@ -950,6 +1004,11 @@ impl EraSupervisor {
         era_id: EraId,
         consensus_result: ProtocolOutcome<ClContext>,
     ) -> Effects<Event> {
+        trace!(
+            era = era_id.value(),
+            outcome = %consensus_result.describe(),
+            "handling consensus outcome"
+        );
         let current_era = match self.current_era() {
             Some(current_era) => current_era,
             None => {
@@ -1284,6 +1343,11 @@ impl SerializedMessage {
 
 #[cfg(test)]
 impl SerializedMessage {
+    /// Wraps the given raw bytes without serializing them.
+    pub(crate) fn from_raw(raw: Vec<u8>) -> Self {
+        SerializedMessage(raw)
+    }
+
     /// Deserializes a message into a the given value.
     ///
     /// # Panics
@@ -4,12 +4,22 @@ use std::{
     borrow::Cow,
     collections::{BTreeMap, HashSet},
     fmt::{self, Display, Formatter},
+    sync::Arc,
 };
 
-use casper_types::{EraId, PublicKey, Timestamp, U512};
+use casper_types::{EraId, PublicKey, TimeDiff, Timestamp, U512};
 use serde::Serialize;
 
-use crate::components::consensus::{highway_core::State, ClContext, HighwayProtocol};
+use crate::{
+    components::consensus::{
+        consensus_protocol::ConsensusProtocol,
+        highway_core::State,
+        protocols::zug::{ParticipationMetrics, ProtocolStats, RoundBlocker, RoundId},
+        utils::{ValidatorIndex, Weight},
+        ClContext, HighwayProtocol, Zug,
+    },
+    types::BlockPayload,
+};
 
 use super::Era;
 
@@ -35,8 +45,10 @@ pub(crate) struct EraDump<'a> {
     /// The validator weights.
     pub(crate) validators: &'a BTreeMap<PublicKey, U512>,
 
-    /// The state of the highway instance associated with the era.
-    pub(crate) highway_state: &'a State<ClContext>,
+    /// The state of the highway instance associated with the era, if it runs Highway.
+    pub(crate) highway_state: Option<&'a State<ClContext>>,
+    /// The state of the Zug instance associated with the era, if it runs Zug.
+    pub(crate) zug_state: Option<ZugDump<'a>>,
 }
 
 impl<'a> Display for EraDump<'a> {
@@ -48,13 +60,17 @@ impl<'a> Display for EraDump<'a> {
 impl<'a> EraDump<'a> {
     /// Creates a new `EraDump` from a given era.
     pub(crate) fn dump_era(era: &'a Era, era_id: EraId) -> Result<Self, Cow<'static, str>> {
-        let highway = era
-            .consensus
-            .as_any()
+        let consensus = era.consensus.as_any();
+        let highway_state = consensus
             .downcast_ref::<HighwayProtocol<ClContext>>()
-            .ok_or(Cow::Borrowed(
-                "could not downcast `ConsensusProtocol` into `HighwayProtocol<ClContext>`",
-            ))?;
+            .map(|highway| highway.highway().state());
+        let zug_state = consensus.downcast_ref::<Zug<ClContext>>().map(ZugDump::new);
+        if highway_state.is_none() && zug_state.is_none() {
+            return Err(Cow::Borrowed(
+                "could not downcast `ConsensusProtocol` into `HighwayProtocol<ClContext>` or \
+                `Zug<ClContext>`",
+            ));
+        }
 
         Ok(EraDump {
             id: era_id,
@@ -64,7 +80,107 @@ impl<'a> EraDump<'a> {
             cannot_propose: &era.cannot_propose,
             accusations: &era.accusations,
             validators: &era.validators,
-            highway_state: highway.highway().state(),
+            highway_state,
+            zug_state,
         })
     }
 }
+
+/// Debug dump of the state of a Zug instance, used for serialization.
+#[derive(Debug, Serialize)]
+pub(crate) struct ZugDump<'a> {
+    /// The round the protocol is currently in.
+    pub(crate) current_round: RoundId,
+    /// The non-finalized rounds that have an accepted proposal but no quorum of votes yet.
+    pub(crate) rounds_pending_finalization: Vec<RoundId>,
+    /// The additional weight of `true` votes each of the rounds pending finalization needs.
+    pub(crate) votes_needed: BTreeMap<RoundId, Weight>,
+    /// The era's scheduled start timestamp and the minimum timestamp of its last block.
+    pub(crate) era_window: (Timestamp, Timestamp),
+    /// The block payloads we currently hold, in proposals in the retained rounds or waiting for
+    /// validation.
+    pub(crate) known_block_values: Vec<&'a Arc<BlockPayload>>,
+    /// The honest validators that haven't signed any echo or vote in any of the retained rounds, and
+    /// haven't led any round with an accepted proposal.
+    pub(crate) never_participated: Vec<ValidatorIndex>,
+    /// The weight of the honest validators that echoed the most-echoed proposal hash in the current
+    /// round, plus the faulty weight, or `None` if there are no echoes in it.
+    pub(crate) current_round_max_echo_weight: Option<Weight>,
+    /// The fault tolerance threshold: if the total weight of faulty validators exceeds this, the
+    /// protocol stops.
+    pub(crate) fault_tolerance_threshold_weight: Weight,
+    /// The total weight of validators currently known to be faulty, including the ones that were
+    /// banned at the start of the era.
+    pub(crate) current_faulty_weight: Weight,
+    /// For each validator whose fault would let a non-finalized round reach a new quorum of echoes
+    /// or votes, the rounds affected.
+    pub(crate) quorum_changes_from_fault: BTreeMap<ValidatorIndex, Vec<RoundId>>,
+    /// The validators that can never be the leader in this era.
+    pub(crate) excluded_leaders: Vec<ValidatorIndex>,
+    /// The round ID of each accepted proposal in the retained rounds, by its height relative to the
+    /// first block in this era.
+    pub(crate) height_to_round: BTreeMap<u64, RoundId>,
+    /// The block of the accepted proposal in each round in `height_to_round`, by round ID. Dummy
+    /// proposals without a block are omitted.
+    pub(crate) accepted_block_values: BTreeMap<RoundId, Arc<BlockPayload>>,
+    /// The round ID, relative height, timestamp and proposer of the latest finalized block.
+    pub(crate) latest_finalized: Option<(RoundId, u64, Timestamp, PublicKey)>,
+    /// The minimum weight of correct validators in the intersection of any two quorums. Safety
+    /// requires this to be positive.
+    pub(crate) quorum_intersection_margin: Weight,
+    /// The validator participation that is periodically logged, as numeric values.
+    pub(crate) participation_metrics: ParticipationMetrics,
+    /// What the current round is waiting for before the next round can start.
+    pub(crate) current_round_blocker: RoundBlocker,
+    /// The number of outgoing messages the instance has created, by kind, and the number of pruned
+    /// and retained rounds.
+    pub(crate) protocol_stats: ProtocolStats,
+    /// The time from receiving to finalizing the proposal, for the most recent finalized rounds in
+    /// which we received the proposal.
+    pub(crate) finalization_latencies: Vec<(RoundId, TimeDiff)>,
+}
+
+impl<'a> ZugDump<'a> {
+    /// Creates a new `ZugDump` from the given Zug instance.
+    fn new(zug: &'a Zug<ClContext>) -> Self {
+        let rounds_pending_finalization = zug.rounds_pending_finalization();
+        let votes_needed = rounds_pending_finalization
+            .iter()
+            .filter_map(|round_id| Some((*round_id, zug.votes_needed(*round_id)?)))
+            .collect();
+        let accepted_block_values = zug
+            .height_to_round()
+            .into_values()
+            .filter_map(|round_id| Some((round_id, zug.accepted_block_value(round_id)?)))
+            .collect();
+        let validator_idxs = || (0..zug.validator_count() as u32).map(ValidatorIndex);
+        let quorum_changes_from_fault = validator_idxs()
+            .map(|idx| (idx, zug.quorum_changes_from_fault(idx)))
+            .filter(|(_, round_ids)| !round_ids.is_empty())
+            .collect();
+        let excluded_leaders = validator_idxs()
+            .filter(|idx| !zug.can_propose(*idx))
+            .collect();
+        ZugDump {
+            current_round: zug.current_round(),
+            rounds_pending_finalization,
+            votes_needed,
+            era_window: zug.era_window(),
+            known_block_values: zug.known_block_values(),
+            never_participated: zug.never_participated(),
+            current_round_max_echo_weight: zug.max_echo_weight(zug.current_round()),
+            fault_tolerance_threshold_weight: zug.fault_tolerance_threshold_weight(),
+            current_faulty_weight: zug.current_faulty_weight(),
+            quorum_changes_from_fault,
+            excluded_leaders,
+            height_to_round: zug.height_to_round(),
+            accepted_block_values,
+            latest_finalized: zug.latest_finalized(),
+            quorum_intersection_margin: zug.quorum_intersection_margin(),
+            participation_metrics: zug.participation_metrics(),
+            current_round_blocker: zug.current_round_blocker(),
+            protocol_stats: zug.protocol_stats(),
+            finalization_latencies: zug.finalization_latencies(),
+        }
+    }
+}
@@ -90,6 +90,11 @@ impl LeaderSequence {
             .unwrap_or_else(panic_or_0)
     }
 
+    /// Returns whether the validator can be selected as a leader at all.
+    pub(crate) fn can_propose(&self, idx: ValidatorIndex) -> bool {
+        self.leaders.get(idx).copied().unwrap_or(false)
+    }
+
     /// Returns the sum of all validators' voting weights.
     pub(crate) fn total_weight(&self) -> Weight {
         *self
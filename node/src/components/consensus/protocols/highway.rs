@@ -38,7 +38,7 @@ use crate::{
         },
         protocols,
         traits::{ConsensusValueT, Context},
-        utils::ValidatorIndex,
+        utils::{ValidatorIndex, Weight},
         ActionId, TimerId,
     },
     types::{Chainspec, NodeId},
@@ -1107,6 +1107,18 @@ where
         vec![]
     }
 
+    fn set_sync_enabled(&mut self, enabled: bool) {
+        warn!(
+            enabled,
+            "Highway does not support disabling the periodic sync"
+        );
+    }
+
+    fn force_start_round(&mut self, round_id: u32, _now: Timestamp) -> ProtocolOutcomes<C> {
+        warn!(round_id, "Highway does not support force-starting a round");
+        vec![]
+    }
+
     fn validators_with_evidence(&self) -> Vec<&C::ValidatorId> {
         self.highway.validators_with_evidence().collect()
     }
@@ -1123,6 +1135,14 @@ where
         self.highway.instance_id()
     }
 
+    fn validator_count(&self) -> usize {
+        self.highway.validators().len()
+    }
+
+    fn total_weight(&self) -> Weight {
+        self.highway.validators().total_weight()
+    }
+
     fn next_round_length(&self) -> Option<TimeDiff> {
         self.highway.next_round_length()
     }
@@ -233,3 +233,16 @@ fn max_rounds_per_era_returns_the_correct_value_for_prod_chainspec_value() {
 
     assert_eq!(219, max_rounds_per_era);
 }
+
+#[test]
+fn validator_count_and_total_weight() {
+    let hw_proto = new_test_highway_protocol(
+        vec![
+            (ALICE_PUBLIC_KEY.clone(), 100),
+            (BOB_PUBLIC_KEY.clone(), 10),
+        ],
+        vec![],
+    );
+    assert_eq!(2, hw_proto.validator_count());
+    assert_eq!(Weight(110), hw_proto.total_weight());
+}
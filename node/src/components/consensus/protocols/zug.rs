@@ -69,9 +69,11 @@ mod wal;
 use std::{
     any::Any,
     cmp::Reverse,
-    collections::{btree_map, BTreeMap, HashMap, HashSet},
+    collections::{btree_map, BTreeMap, HashMap, HashSet, VecDeque},
+    convert::TryFrom,
     fmt::Debug,
-    iter,
+    iter, mem,
+    ops::RangeInclusive,
     path::PathBuf,
 };
 
@@ -79,6 +81,7 @@ use datasize::DataSize;
 use either::Either;
 use itertools::Itertools;
 use rand::{seq::IteratorRandom, Rng};
+use serde::Serialize;
 use tracing::{debug, error, event, info, warn, Level};
 
 use casper_types::{system::auction::BLOCK_REWARD, TimeDiff, Timestamp, U512};
@@ -99,8 +102,9 @@ use crate::{
     types::{Chainspec, NodeId},
     utils, NodeRng,
 };
+use config::SyncRoundStrategy;
 use fault::Fault;
-use message::{Content, SignedMessage, SyncResponse};
+use message::{Content, FinalityCertificate, SignedMessage, SyncResponse};
 use params::Params;
 use participation::{Participation, ParticipationStatus};
 use proposal::{HashedProposal, Proposal};
@@ -108,6 +112,7 @@ use round::Round;
 use wal::{Entry, ReadWal, WriteWal};
 
 pub(crate) use message::{Message, SyncRequest};
+pub(crate) use participation::ParticipationMetrics;
 
 /// The timer for syncing with a random peer.
 const TIMER_ID_SYNC_PEER: TimerId = TimerId(0);
@@ -115,16 +120,82 @@ const TIMER_ID_SYNC_PEER: TimerId = TimerId(0);
 const TIMER_ID_UPDATE: TimerId = TimerId(1);
 /// The timer for logging inactive validators.
 const TIMER_ID_LOG_PARTICIPATION: TimerId = TimerId(2);
+/// The timer for casting delayed `true` votes.
+const TIMER_ID_VOTE: TimerId = TimerId(3);
+/// The timer for requesting proposals that we are missing despite quorums of echoes and votes.
+const TIMER_ID_MISSING_PROPOSALS: TimerId = TimerId(4);
 
 /// The maximum number of future rounds we instantiate if we get messages from rounds that we
 /// haven't started yet.
 const MAX_FUTURE_ROUNDS: u32 = 7200; // Don't drop messages in 2-hour eras with 1-second rounds.
 
+/// The maximum number of proposals from future rounds we keep for later, if deferring them is
+/// enabled. Further ones are handled right away.
+const MAX_DEFERRED_PROPOSALS: usize = 1000;
+
+/// The maximum number of sync requests we send at once if a peer's sync request shows that we are
+/// behind.
+const MAX_SYNC_BURST: u32 = 10;
+
+/// The maximum number of recent finalization latencies we keep.
+const MAX_FINALIZATION_LATENCIES: usize = 1000;
+
+/// The maximum relative deviation of the sync peer timer from the configured interval. This
+/// prevents nodes that started their eras at the same time from syncing in lockstep.
+const SYNC_PEER_JITTER: f64 = 0.1;
+
 /// Identifies a single [`Round`] in the protocol.
 pub(crate) type RoundId = u32;
 
 type ProposalsAwaitingParent = HashSet<(RoundId, NodeId)>;
 type ProposalsAwaitingValidation<C> = HashSet<(RoundId, HashedProposal<C>, NodeId)>;
+type DeferredProposal<C> = (Proposal<C>, SignedMessage<C>, NodeId);
+
+/// The number of outgoing messages this protocol instance has created, by kind of message and by
+/// kind of outcome, and the number of rounds it has pruned and retained.
+///
+/// Evidence sent to a peer on request via `send_evidence` is not counted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, DataSize, Serialize)]
+pub(crate) struct ProtocolStats {
+    /// Our own proposals gossiped to all peers. The leader's echo included in them is not counted
+    /// separately.
+    pub(crate) proposals: u64,
+    /// Echoes gossiped to all peers.
+    pub(crate) echoes: u64,
+    /// Votes gossiped to all peers.
+    pub(crate) votes: u64,
+    /// Evidence of faults gossiped to all peers.
+    pub(crate) evidence: u64,
+    /// Sync requests, which are always sent to a random peer.
+    pub(crate) sync_requests: u64,
+    /// Responses to sync requests.
+    pub(crate) sync_responses: u64,
+    /// Messages of any kind gossiped to all peers.
+    pub(crate) gossip_messages: u64,
+    /// Messages sent to a single peer.
+    pub(crate) targeted_messages: u64,
+    /// Messages sent to a random peer.
+    pub(crate) messages_to_random_peer: u64,
+    /// Rounds removed from memory. Rounds are only removed once the era is no longer current and
+    /// the instance is switched to evidence-only mode.
+    pub(crate) pruned_rounds: u64,
+    /// The number of rounds currently held in memory.
+    pub(crate) retained_rounds: u64,
+}
+
+/// What the current round is waiting for before the next round can start.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub(crate) enum RoundBlocker {
+    /// We don't have the leader's proposal, and nobody has voted to skip the round yet.
+    NoProposal,
+    /// We have the proposal, but not a quorum of echoes for it.
+    AwaitingEchoQuorum,
+    /// The proposal has a quorum of echoes, but its parent is not accepted yet or a round between
+    /// the parent and this one is not skippable yet.
+    AwaitingParentAcceptance,
+    /// Some validators voted `false`, but not yet a quorum that would make the round skippable.
+    AwaitingVotes,
+}
 
 /// Contains the portion of the state required for an active validator to participate in the
 /// protocol.
@@ -170,6 +241,12 @@ where
         HashMap<RoundId, HashMap<HashedProposal<C>, ProposalsAwaitingParent>>,
     /// Incoming blocks we can't add yet because we are waiting for validation.
     proposals_waiting_for_validation: HashMap<ProposedBlock<C>, ProposalsAwaitingValidation<C>>,
+    /// Proposals from future rounds, with the accompanying echo and the sender, by round ID and
+    /// the echo's validator index. They are handled once their round becomes current. This is
+    /// only used if `defer_future_proposals` is enabled.
+    deferred_proposals: BTreeMap<RoundId, BTreeMap<ValidatorIndex, DeferredProposal<C>>>,
+    /// The total number of entries in `deferred_proposals`.
+    deferred_proposal_count: usize,
     /// If we requested a new block from the block proposer component this contains the proposal's
     /// round ID and the parent's round ID, if there is a parent.
     pending_proposal: Option<(BlockContext<C>, RoundId, Option<RoundId>)>,
@@ -186,6 +263,9 @@ where
     first_non_finalized_round_id: RoundId,
     /// The lowest round that needs to be considered in `upgrade`.
     maybe_dirty_round_id: Option<RoundId>,
+    /// Rounds that were outside the `fault_rescan_window` when a fault was detected. They are
+    /// checked for new quorums once they become current.
+    maybe_rescan_round_ids: Option<RangeInclusive<RoundId>>,
     /// The lowest non-skippable round without an accepted value.
     current_round: RoundId,
     /// The time when the current round started.
@@ -194,6 +274,8 @@ where
     progress_detected: bool,
     /// Whether or not the protocol is currently paused
     paused: bool,
+    /// Whether we periodically send sync requests to random peers.
+    sync_enabled: bool,
     /// The next update we have set a timer for. This helps deduplicate redundant calls to
     /// `update`.
     next_scheduled_update: Timestamp,
@@ -201,6 +283,15 @@ where
     write_wal: Option<WriteWal<C>>,
     /// The rewards based on the finalized rounds so far.
     rewards: BTreeMap<C::ValidatorId, u64>,
+    /// The time from receiving to finalizing the proposal, for the most recent finalized rounds.
+    finalization_latencies: VecDeque<(RoundId, TimeDiff)>,
+    /// The number of outgoing messages we created, by kind.
+    stats: ProtocolStats,
+    /// Rounds with accepted proposals we haven't voted for yet, with the time when we will vote.
+    delayed_votes: BTreeMap<RoundId, Timestamp>,
+    /// The earliest time when a sync request referring to a future round can make us send another
+    /// burst of sync requests.
+    next_sync_burst: Timestamp,
 }
 
 impl<C: Context + 'static> Zug<C> {
@@ -225,7 +316,9 @@ impl<C: Context + 'static> Zug<C> {
                     * (config.proposal_grace_period as f64 / 100.0 + 1.0)
             });
 
-        let mut can_propose: ValidatorMap<bool> = weights.iter().map(|_| true).collect();
+        // Validators without any weight could never get their proposals accepted, so they are
+        // excluded from the leader sequence, like the ones that were inactive.
+        let mut can_propose: ValidatorMap<bool> = weights.iter().map(|w| !w.is_zero()).collect();
         for vidx in validators.iter_cannot_propose_idx() {
             can_propose[vidx] = false;
         }
@@ -249,9 +342,12 @@ impl<C: Context + 'static> Zug<C> {
             leader_sequence,
             proposals_waiting_for_parent: HashMap::new(),
             proposals_waiting_for_validation: HashMap::new(),
+            deferred_proposals: BTreeMap::new(),
+            deferred_proposal_count: 0,
             rounds: BTreeMap::new(),
             first_non_finalized_round_id: 0,
             maybe_dirty_round_id: None,
+            maybe_rescan_round_ids: None,
             current_round: 0,
             current_round_start: Timestamp::MAX,
             evidence_only: false,
@@ -265,9 +361,14 @@ impl<C: Context + 'static> Zug<C> {
             pending_proposal: None,
             progress_detected: false,
             paused: false,
+            sync_enabled: true,
             next_scheduled_update: Timestamp::MAX,
             write_wal: None,
             rewards,
+            finalization_latencies: VecDeque::new(),
+            stats: ProtocolStats::default(),
+            delayed_votes: BTreeMap::new(),
+            next_sync_burst: Timestamp::zero(),
         }
     }
 
@@ -338,13 +439,24 @@ impl<C: Context + 'static> Zug<C> {
 
     /// Prints a log statement listing the inactive and faulty validators.
     fn log_participation(&self) {
+        let participation = self.participation();
+        info!(
+            our_idx = self.our_idx(),
+            ?participation,
+            "validator participation"
+        );
+    }
+
+    /// Returns the faulty and inactive validators and their share of the total stake.
+    fn participation(&self) -> Participation<C> {
         let mut inactive_w: u64 = 0;
         let mut faulty_w: u64 = 0;
         let total_w = self.validators.total_weight().0;
         let mut inactive_validators = Vec::new();
         let mut faulty_validators = Vec::new();
+        let statuses = ParticipationStatus::for_all(self);
         for (idx, v_id) in self.validators.enumerate_ids() {
-            if let Some(status) = ParticipationStatus::for_index(idx, self) {
+            if let Some(status) = statuses[idx] {
                 match status {
                     ParticipationStatus::Equivocated
                     | ParticipationStatus::EquivocatedInOtherEra => {
@@ -362,18 +474,70 @@ impl<C: Context + 'static> Zug<C> {
         faulty_validators.sort_by_key(|(idx, _, status)| (Reverse(*status), *idx));
         let inactive_w_100 = u128::from(inactive_w).saturating_mul(100);
         let faulty_w_100 = u128::from(faulty_w).saturating_mul(100);
-        let participation = Participation::<C> {
+        Participation::<C> {
             instance_id: *self.instance_id(),
             inactive_stake_percent: utils::div_round(inactive_w_100, u128::from(total_w)) as u8,
             faulty_stake_percent: utils::div_round(faulty_w_100, u128::from(total_w)) as u8,
             inactive_validators,
             faulty_validators,
+        }
+    }
+
+    /// Logs a warning for every non-finalized round that has a quorum of echoes and a quorum of
+    /// `true` votes, but where we are still missing the proposal itself, and requests that round
+    /// from a random peer. Finalization cannot progress past such a round until we get the
+    /// proposal.
+    ///
+    /// Like the other sync requests, this runs every sync state interval, and not at all if
+    /// syncing is disabled.
+    fn handle_missing_proposals_timer(
+        &self,
+        now: Timestamp,
+        rng: &mut NodeRng,
+    ) -> ProtocolOutcomes<C> {
+        if self.evidence_only || self.finalized_switch_block() {
+            return vec![]; // Era has ended. No further progress is expected.
+        }
+        let interval = match self.config.sync_state_interval {
+            Some(interval) => interval,
+            None => return vec![], // Syncing is disabled.
         };
-        info!(
-            our_idx = self.our_idx(),
-            ?participation,
-            "validator participation"
-        );
+        let mut outcomes = vec![];
+        if self.sync_enabled {
+            outcomes.extend(self.request_missing_proposals(rng));
+        }
+        outcomes.push(ProtocolOutcome::ScheduleTimer(
+            now + interval,
+            TIMER_ID_MISSING_PROPOSALS,
+        ));
+        outcomes
+    }
+
+    /// Returns requests for all non-finalized rounds where we have a quorum of echoes and `true`
+    /// votes but not the proposal.
+    fn request_missing_proposals(&self, rng: &mut NodeRng) -> ProtocolOutcomes<C> {
+        let mut outcomes = vec![];
+        for (round_id, round) in self.rounds.range(self.first_non_finalized_round_id..) {
+            let hash = match round.quorum_echoes() {
+                Some(hash) if round.quorum_votes() == Some(true) => hash,
+                _ => continue,
+            };
+            if round.proposal().map(HashedProposal::hash) == Some(&hash) {
+                continue;
+            }
+            warn!(
+                our_idx = self.our_idx(),
+                round_id,
+                %hash,
+                "have quorums of echoes and votes, but missing the proposal"
+            );
+            let first_validator_idx =
+                ValidatorIndex(rng.gen_range(0..self.validators.len() as u32));
+            outcomes.push(ProtocolOutcome::CreatedRequestToRandomPeer(
+                self.sync_request_for(*round_id, first_validator_idx),
+            ));
+        }
+        outcomes
     }
 
     /// Returns whether the switch block has already been finalized.
@@ -423,30 +587,118 @@ impl<C: Context + 'static> Zug<C> {
         if self.evidence_only || self.finalized_switch_block() {
             return vec![]; // Era has ended. No further progress is expected.
         }
-        debug!(
-            our_idx = self.our_idx(),
-            instance_id = ?self.instance_id(),
-            "syncing with random peer",
-        );
-        // Inform a peer about our protocol state and schedule the next request.
-        let first_validator_idx = ValidatorIndex(rng.gen_range(0..self.validators.len() as u32));
-        let round_id = (self.first_non_finalized_round_id..=self.current_round)
-            .choose(rng)
-            .unwrap_or(self.current_round);
-        let payload = self.create_sync_request(first_validator_idx, round_id);
-        let mut outcomes = vec![ProtocolOutcome::CreatedRequestToRandomPeer(
-            SerializedMessage::from_message(&payload),
-        )];
+        let mut outcomes = vec![];
+        if self.sync_enabled {
+            debug!(
+                our_idx = self.our_idx(),
+                instance_id = ?self.instance_id(),
+                "syncing with random peer",
+            );
+            // Inform a peer about our protocol state.
+            let first_validator_idx =
+                ValidatorIndex(rng.gen_range(0..self.validators.len() as u32));
+            let round_id = self.sync_round_id(rng);
+            outcomes.push(ProtocolOutcome::CreatedRequestToRandomPeer(
+                self.sync_request_for(round_id, first_validator_idx),
+            ));
+        }
         // Periodically sync the state with a random peer.
         if let Some(interval) = self.config.sync_state_interval {
+            let factor = rng.gen_range((1.0 - SYNC_PEER_JITTER)..=(1.0 + SYNC_PEER_JITTER));
+            let delay = TimeDiff::from_millis((interval.millis() as f64 * factor) as u64);
             outcomes.push(ProtocolOutcome::ScheduleTimer(
-                now + interval,
+                now + delay,
                 TIMER_ID_SYNC_PEER,
             ));
         }
         outcomes
     }
 
+    /// Called when a peer's sync request refers to a round beyond our current one that we don't
+    /// have, i.e. when we are behind. Requests the rounds from our current one up to the peer's,
+    /// but at most `MAX_SYNC_BURST` of them. To avoid amplification, this happens at most once
+    /// per sync state interval.
+    fn handle_sync_request_from_future(
+        &mut self,
+        round_id: RoundId,
+        rng: &mut NodeRng,
+        now: Timestamp,
+    ) -> ProtocolOutcomes<C> {
+        let interval = match self.config.sync_state_interval {
+            Some(interval) => interval,
+            None => return vec![], // Syncing is disabled.
+        };
+        if !self.sync_enabled
+            || self.evidence_only
+            || self.finalized_switch_block()
+            || now < self.next_sync_burst
+            || round_id > self.current_round.saturating_add(MAX_FUTURE_ROUNDS)
+        {
+            return vec![];
+        }
+        self.next_sync_burst = now + interval;
+        let end_round_id = round_id.min(
+            self.current_round
+                .saturating_add(MAX_SYNC_BURST)
+                .saturating_sub(1),
+        );
+        debug!(
+            our_idx = self.our_idx(),
+            peer_round_id = round_id,
+            current_round = self.current_round,
+            "peer is ahead of us; requesting the rounds in between"
+        );
+        (self.current_round..=end_round_id)
+            .map(|round_id| {
+                let first_validator_idx =
+                    ValidatorIndex(rng.gen_range(0..self.validators.len() as u32));
+                ProtocolOutcome::CreatedRequestToRandomPeer(
+                    self.sync_request_for(round_id, first_validator_idx),
+                )
+            })
+            .collect()
+    }
+
+    /// Returns the round to include in a sync request to a random peer, according to the
+    /// configured strategy.
+    fn sync_round_id(&self, rng: &mut NodeRng) -> RoundId {
+        match self.config.sync_round_strategy {
+            SyncRoundStrategy::Random => (self.first_non_finalized_round_id..=self.current_round)
+                .choose(rng)
+                .unwrap_or(self.current_round),
+            SyncRoundStrategy::LowestPending => {
+                self.first_non_finalized_round_id.min(self.current_round)
+            }
+            SyncRoundStrategy::Current => self.current_round,
+        }
+    }
+
+    /// Casts the delayed `true` votes that are due.
+    fn handle_vote_timer(&mut self, now: Timestamp) -> ProtocolOutcomes<C> {
+        let due_round_ids: Vec<RoundId> = self
+            .delayed_votes
+            .iter()
+            .filter(|(_, vote_time)| **vote_time <= now)
+            .map(|(round_id, _)| *round_id)
+            .collect();
+        let mut outcomes = vec![];
+        for round_id in due_round_ids {
+            self.delayed_votes.remove(&round_id);
+            // The round might have been pruned in the meantime. If we already voted `false`,
+            // `create_message` won't sign a conflicting vote.
+            if !self.rounds.contains_key(&round_id) {
+                continue;
+            }
+            let vote_outcomes = self.create_and_gossip_message(round_id, Content::Vote(true));
+            if !vote_outcomes.is_empty() {
+                self.mark_dirty(round_id);
+            }
+            outcomes.extend(vote_outcomes);
+        }
+        outcomes.extend(self.update(now));
+        outcomes
+    }
+
     /// Prints a log message if the message is a proposal.
     fn log_proposal(&self, proposal: &HashedProposal<C>, round_id: RoundId, msg: &str) {
         let creator_index = self.leader(round_id);
@@ -473,6 +725,18 @@ impl<C: Context + 'static> Zug<C> {
         );
     }
 
+    /// Returns a serialized sync request for the given round and first validator index.
+    ///
+    /// The first validator index is the only input that isn't part of our state, so two nodes with
+    /// the same state produce identical requests for the same index and can compare them directly.
+    fn sync_request_for(
+        &self,
+        round_id: RoundId,
+        first_validator_idx: ValidatorIndex,
+    ) -> SerializedMessage {
+        SerializedMessage::from_message(&self.create_sync_request(first_validator_idx, round_id))
+    }
+
     /// Creates a `SyncRequest` message to inform a peer about our view of the given round, so that
     /// the peer can send us any data we are missing.
     ///
@@ -502,12 +766,13 @@ impl<C: Context + 'static> Zug<C> {
         let false_votes =
             self.validator_bit_field(first_validator_idx, round.votes(false).keys_some());
         // We only request information about the proposal with the most echoes, by weight.
+        // Ties are broken by hash, so that the request doesn't depend on the map's iteration order.
         // TODO: If there's no quorum, should we prefer the one for which we have the leader's echo?
         let proposal_hash = round.quorum_echoes().or_else(|| {
             round
                 .echoes()
                 .iter()
-                .max_by_key(|(_, echo_map)| self.sum_weights(echo_map.keys()))
+                .max_by_key(|(hash, echo_map)| (self.sum_weights(echo_map.keys()), **hash))
                 .map(|(hash, _)| *hash)
         });
         let has_proposal = round.proposal().map(HashedProposal::hash) == proposal_hash.as_ref();
@@ -589,35 +854,6 @@ impl<C: Context + 'static> Zug<C> {
         })
     }
 
-    /// Returns whether `v_idx` is covered by a validator index that starts at `first_idx`.
-    fn validator_bit_field_includes(
-        &self,
-        ValidatorIndex(first_idx): ValidatorIndex,
-        ValidatorIndex(v_idx): ValidatorIndex,
-    ) -> bool {
-        let validator_count = self.validators.len() as u32;
-        if first_idx >= validator_count {
-            return false;
-        }
-        let high_bit = u128::BITS.saturating_sub(1);
-        // The overflow bit is the 33rd bit of the actual sum.
-        let (last_idx, last_idx_overflow) = first_idx.overflowing_add(high_bit);
-        if v_idx >= first_idx {
-            // v_idx is at least first_idx, so it's in the range unless it's higher than the last
-            // index, taking into account its 33rd bit.
-            last_idx_overflow || v_idx <= last_idx
-        } else {
-            // v_idx is less than first_idx. But if going from the first to the last index we wrap
-            // around, we might still arrive at v_idx:
-            let (v_idx2, v_idx2_overflow) = v_idx.overflowing_add(validator_count);
-            if v_idx2_overflow == last_idx_overflow {
-                v_idx2 <= last_idx
-            } else {
-                last_idx_overflow
-            }
-        }
-    }
-
     /// Returns the leader in the specified round.
     pub(crate) fn leader(&self, round_id: RoundId) -> ValidatorIndex {
         if let Some(round) = self.round(round_id) {
@@ -644,6 +880,19 @@ impl<C: Context + 'static> Zug<C> {
             Content::Vote(_) => self.has_voted(round_id, validator_idx),
         };
         if already_signed {
+            // Signing the same content twice is harmless, but a conflicting message would make us
+            // equivocate. That must never happen, so it indicates a bug.
+            if !self
+                .round(round_id)
+                .map_or(false, |round| round.contains(&content, validator_idx))
+            {
+                error!(
+                    our_idx = validator_idx.0,
+                    %round_id,
+                    ?content,
+                    "refusing to sign a message that conflicts with our own earlier message"
+                );
+            }
             return None;
         }
         let signed_msg = SignedMessage::sign_new(
@@ -680,6 +929,13 @@ impl<C: Context + 'static> Zug<C> {
         content: Content<C>,
     ) -> ProtocolOutcomes<C> {
         let maybe_signed_msg = self.create_message(round_id, content);
+        if maybe_signed_msg.is_some() {
+            let counter = match content {
+                Content::Echo(_) => &mut self.stats.echoes,
+                Content::Vote(_) => &mut self.stats.votes,
+            };
+            *counter = counter.saturating_add(1);
+        }
         maybe_signed_msg
             .into_iter()
             .map(|signed_msg| {
@@ -741,29 +997,24 @@ impl<C: Context + 'static> Zug<C> {
             round.remove_votes_and_echoes(validator_idx);
         }
 
-        // Recompute quorums; if any new quorums are found, call `update`.
-        for round_id in
-            self.first_non_finalized_round_id..=self.rounds.keys().last().copied().unwrap_or(0)
-        {
-            if !self.rounds.contains_key(&round_id) {
-                continue;
-            }
-            if self.rounds[&round_id].quorum_echoes().is_none() {
-                let hashes = self.rounds[&round_id]
-                    .echoes()
-                    .keys()
-                    .copied()
-                    .collect_vec();
-                if hashes
-                    .into_iter()
-                    .any(|hash| self.check_new_echo_quorum(round_id, hash))
-                {
-                    self.mark_dirty(round_id);
-                }
-            }
-            if self.check_new_vote_quorum(round_id, true)
-                || self.check_new_vote_quorum(round_id, false)
-            {
+        // Recompute quorums; if any new quorums are found, call `update`. To keep the cost per
+        // message bounded, rounds beyond the window are only checked once they become current.
+        let max_round_id = self.rounds.keys().last().copied().unwrap_or(0);
+        let max_rescan_round_id = self
+            .current_round
+            .saturating_add(self.config.fault_rescan_window)
+            .min(max_round_id);
+        if max_rescan_round_id < max_round_id {
+            let first_rescan_round_id = match &self.maybe_rescan_round_ids {
+                Some(round_ids) => *round_ids.start(),
+                None => RoundId::MAX,
+            };
+            self.maybe_rescan_round_ids = Some(
+                first_rescan_round_id.min(max_rescan_round_id.saturating_add(1))..=max_round_id,
+            );
+        }
+        for round_id in self.first_non_finalized_round_id..=max_rescan_round_id {
+            if self.rounds.contains_key(&round_id) && self.check_new_quorums(round_id) {
                 self.mark_dirty(round_id);
             }
         }
@@ -807,6 +1058,20 @@ impl<C: Context + 'static> Zug<C> {
             None => return (vec![], None),
         };
 
+        // If the round is finalized and the requester has the proposal but not enough `true`
+        // votes, a finality certificate gives them all the signatures they need at once, even if
+        // those are more than `max_sync_response_messages`.
+        if has_proposal && proposal_hash.is_some() && proposal_hash == round.quorum_echoes() {
+            let their_true_votes =
+                self.iter_validator_bit_field(first_validator_idx, true_votes & !faulty);
+            if !self.is_quorum(their_true_votes) {
+                if let Some(cert) = self.finality_certificate(round_id) {
+                    let msg = Message::FinalityCertificate(cert);
+                    return (vec![], Some(SerializedMessage::from_message(&msg)));
+                }
+            }
+        }
+
         // If the peer has no or a wrong proposal we assume they don't have any echoes for the
         // correct one. We don't send them the right proposal, though: they might already have it.
         if round.quorum_echoes() != proposal_hash && round.quorum_echoes().is_some() {
@@ -817,6 +1082,9 @@ impl<C: Context + 'static> Zug<C> {
 
         // The bit field of validators we know to be faulty.
         let our_faulty = self.validator_bit_field(first_validator_idx, self.faults.keys().cloned());
+        // The number of signed messages we can still add to the response. Echoes have priority,
+        // then votes, evidence and proofs of activity. The requester will ask again for the rest.
+        let mut budget = self.config.max_sync_response_messages as usize;
         // The echo signatures and proposal/hash we will send in the response.
         let mut proposal_or_hash = None;
         let mut echo_sigs = BTreeMap::new();
@@ -829,57 +1097,93 @@ impl<C: Context + 'static> Zug<C> {
                 our_echoes =
                     self.validator_bit_field(first_validator_idx, echo_map.keys().cloned());
                 let missing_echoes = our_echoes & !(echoes | faulty | our_faulty);
+                // Only send as many as they need for a quorum. Faulty validators' weight doesn't
+                // count, since the requester might not know about the faults yet.
+                let quorum_threshold = self.quorum_threshold();
+                let mut echo_weight: Weight = self
+                    .iter_validator_bit_field(first_validator_idx, echoes & !(faulty | our_faulty))
+                    .map(|v_idx| self.validators.weight(v_idx))
+                    .sum();
+                // If they don't have the proposal make sure the leader's echo comes first, whether
+                // or not the leader falls into the requested range. It counts against the budget
+                // like all the others.
+                let leader_idx = round.leader();
+                if !has_proposal && budget > 0 {
+                    if let Some(signature) = echo_map.get(&leader_idx) {
+                        echo_sigs.insert(leader_idx, *signature);
+                        let leader_bit =
+                            self.validator_bit_field(first_validator_idx, iter::once(leader_idx));
+                        if leader_bit & (echoes | faulty | our_faulty) == 0 {
+                            echo_weight += self.validators.weight(leader_idx);
+                        }
+                    }
+                }
                 for v_idx in self.iter_validator_bit_field(first_validator_idx, missing_echoes) {
+                    if echo_weight > quorum_threshold || echo_sigs.len() >= budget {
+                        break;
+                    }
+                    if echo_sigs.contains_key(&v_idx) {
+                        continue; // The leader's echo is already included.
+                    }
                     echo_sigs.insert(v_idx, echo_map[&v_idx]);
+                    echo_weight += self.validators.weight(v_idx);
                 }
+                // The echoes we don't send don't prove that those validators are active.
+                our_echoes &= !missing_echoes
+                    | self.validator_bit_field(first_validator_idx, echo_sigs.keys().copied());
                 if has_proposal {
                     proposal_or_hash = Some(Either::Right(hash));
-                } else {
-                    // If they don't have the proposal make sure we include the leader's echo.
-                    let leader_idx = round.leader();
-                    if !self.validator_bit_field_includes(first_validator_idx, leader_idx) {
-                        if let Some(signature) = echo_map.get(&leader_idx) {
-                            echo_sigs.insert(leader_idx, *signature);
-                        }
-                    }
-                    if let Some(proposal) = round.proposal() {
-                        if *proposal.hash() == hash {
-                            proposal_or_hash = Some(Either::Left(proposal.inner().clone()));
-                        }
+                } else if let Some(proposal) = round.proposal() {
+                    if *proposal.hash() == hash {
+                        proposal_or_hash = Some(Either::Left(proposal.inner().clone()));
                     }
                 }
             }
         }
+        budget = budget.saturating_sub(echo_sigs.len());
 
         // Send them votes they are missing, but exclude faulty validators. If there already is a
         // quorum omit the votes that go against the quorum, since they are irrelevant.
-        let our_true_votes: u128 = if round.quorum_votes() == Some(false) {
+        let mut our_true_votes: u128 = if round.quorum_votes() == Some(false) {
             0
         } else {
             self.validator_bit_field(first_validator_idx, round.votes(true).keys_some())
         };
         let missing_true_votes = our_true_votes & !(true_votes | faulty | our_faulty);
-        let true_vote_sigs = self
+        let true_vote_sigs: BTreeMap<_, _> = self
             .iter_validator_bit_field(first_validator_idx, missing_true_votes)
+            .take(budget)
             .map(|v_idx| (v_idx, round.votes(true)[v_idx].unwrap()))
             .collect();
-        let our_false_votes: u128 = if round.quorum_votes() == Some(true) {
+        our_true_votes &= !missing_true_votes
+            | self.validator_bit_field(first_validator_idx, true_vote_sigs.keys().copied());
+        budget = budget.saturating_sub(true_vote_sigs.len());
+        let mut our_false_votes: u128 = if round.quorum_votes() == Some(true) {
             0
         } else {
             self.validator_bit_field(first_validator_idx, round.votes(false).keys_some())
         };
         let missing_false_votes = our_false_votes & !(false_votes | faulty | our_faulty);
-        let false_vote_sigs = self
+        let false_vote_sigs: BTreeMap<_, _> = self
             .iter_validator_bit_field(first_validator_idx, missing_false_votes)
+            .take(budget)
             .map(|v_idx| (v_idx, round.votes(false)[v_idx].unwrap()))
             .collect();
+        our_false_votes &= !missing_false_votes
+            | self.validator_bit_field(first_validator_idx, false_vote_sigs.keys().copied());
+        budget = budget.saturating_sub(false_vote_sigs.len());
 
         let mut outcomes = vec![];
 
         // Add evidence for validators they don't know are faulty.
         let missing_faulty = our_faulty & !faulty;
         let mut evidence = vec![];
-        for v_idx in self.iter_validator_bit_field(first_validator_idx, missing_faulty) {
+        let evidence_idxs: Vec<ValidatorIndex> = self
+            .iter_validator_bit_field(first_validator_idx, missing_faulty)
+            .take(budget)
+            .collect();
+        budget = budget.saturating_sub(evidence_idxs.len());
+        for v_idx in evidence_idxs {
             match &self.faults[&v_idx] {
                 Fault::Banned => {
                     info!(
@@ -909,6 +1213,7 @@ impl<C: Context + 'static> Zug<C> {
         let signed_messages = self
             .iter_validator_bit_field(first_validator_idx, missing_active)
             .filter_map(|v_idx| self.active[v_idx].clone())
+            .take(budget)
             .collect();
 
         // Send the serialized sync response to the requester
@@ -999,6 +1304,50 @@ impl<C: Context + 'static> Zug<C> {
         outcomes
     }
 
+    /// Verifies a finality certificate sent in response to our sync request, and handles the
+    /// echoes and `true` votes it contains.
+    fn handle_finality_certificate(
+        &mut self,
+        cert: FinalityCertificate<C>,
+        sender: NodeId,
+        now: Timestamp,
+    ) -> ProtocolOutcomes<C> {
+        if !self.verify_finality_certificate(&cert) {
+            warn!(
+                our_idx = self.our_idx(),
+                round_id = cert.round_id,
+                %sender,
+                "invalid finality certificate"
+            );
+            return vec![ProtocolOutcome::Disconnect(sender)];
+        }
+        let FinalityCertificate {
+            round_id,
+            instance_id,
+            proposal_hash,
+            echoes,
+            true_votes,
+        } = cert;
+        let echoes = echoes.into_iter().map(|(validator_idx, signature)| {
+            (Content::Echo(proposal_hash), validator_idx, signature)
+        });
+        let true_votes = true_votes
+            .into_iter()
+            .map(|(validator_idx, signature)| (Content::Vote(true), validator_idx, signature));
+        let mut outcomes = vec![];
+        for (content, validator_idx, signature) in echoes.chain(true_votes) {
+            let signed_msg = SignedMessage {
+                round_id,
+                instance_id,
+                content,
+                validator_idx,
+                signature,
+            };
+            outcomes.extend(self.handle_signed_message(signed_msg, sender, now));
+        }
+        outcomes
+    }
+
     /// The main entry point for signed echoes or votes. This function mostly authenticates
     /// and authorizes the message, passing it to [`add_content`] if it passes snuff for the
     /// main protocol logic.
@@ -1057,6 +1406,7 @@ impl<C: Context + 'static> Zug<C> {
             let evidence_msg = Message::Evidence(signed_msg.clone(), content2, signature2);
             let mut outcomes =
                 self.handle_fault(signed_msg, validator_id, content2, signature2, now);
+            self.stats.evidence = self.stats.evidence.saturating_add(1);
             outcomes.push(ProtocolOutcome::CreatedGossipMessage(
                 SerializedMessage::from_message(&evidence_msg),
             ));
@@ -1253,7 +1603,8 @@ impl<C: Context + 'static> Zug<C> {
             vec![]
         };
 
-        let mut outcomes = self.validate_proposal(round_id, hashed_prop, ancestor_values, sender);
+        let mut outcomes =
+            self.validate_proposal(round_id, hashed_prop, ancestor_values, sender, now);
         outcomes.extend(self.update(now));
         outcomes
     }
@@ -1271,6 +1622,26 @@ impl<C: Context + 'static> Zug<C> {
         false
     }
 
+    /// Updates the round's outcome and returns `true` if there is a new quorum of echoes or votes.
+    fn check_new_quorums(&mut self, round_id: RoundId) -> bool {
+        let mut new_quorum = false;
+        if self.rounds[&round_id].quorum_echoes().is_none() {
+            let hashes = self.rounds[&round_id]
+                .echoes()
+                .keys()
+                .copied()
+                .collect_vec();
+            new_quorum = hashes
+                .into_iter()
+                .any(|hash| self.check_new_echo_quorum(round_id, hash));
+        }
+        if self.check_new_vote_quorum(round_id, true) || self.check_new_vote_quorum(round_id, false)
+        {
+            new_quorum = true;
+        }
+        new_quorum
+    }
+
     /// Updates the round's outcome and returns `true` if there is a new quorum of votes with the
     /// given value.
     fn check_new_vote_quorum(&mut self, round_id: RoundId, vote: bool) -> bool {
@@ -1331,6 +1702,14 @@ impl<C: Context + 'static> Zug<C> {
             match read_wal.read_next_entry() {
                 Ok(Some(next_entry)) => match next_entry {
                     Entry::SignedMessage(next_message) => {
+                        if self.active.get(next_message.validator_idx).is_none() {
+                            warn!(
+                                our_idx,
+                                ?next_message,
+                                "validator index in WAL entry out of range; skipping it"
+                            );
+                            continue;
+                        }
                         if !self.add_content(next_message) {
                             error!(our_idx, "Could not add content from WAL.");
                             return outcomes;
@@ -1372,7 +1751,7 @@ impl<C: Context + 'static> Zug<C> {
                         }
                         if self
                             .round_mut(corresponding_round_id)
-                            .insert_proposal(HashedProposal::new(next_proposal.clone()))
+                            .insert_proposal(HashedProposal::new(next_proposal.clone()), now)
                         {
                             self.mark_dirty(corresponding_round_id);
                             if let Some(block) = next_proposal.maybe_block {
@@ -1462,9 +1841,22 @@ impl<C: Context + 'static> Zug<C> {
 
     /// Adds a signed message content to the state.
     /// Does not call `update` and does not detect faults.
+    ///
+    /// Returns `false` if the message was already known or its validator index is out of range.
+    /// The latter can only happen for messages that haven't been checked, e.g. read from the WAL.
     fn add_content(&mut self, signed_msg: SignedMessage<C>) -> bool {
-        if self.active[signed_msg.validator_idx].is_none() {
-            self.active[signed_msg.validator_idx] = Some(signed_msg.clone());
+        let active_msg = if let Some(active_msg) = self.active.get_mut(signed_msg.validator_idx) {
+            active_msg
+        } else {
+            error!(
+                our_idx = self.our_idx(),
+                ?signed_msg,
+                "validator index out of range; dropping message"
+            );
+            return false;
+        };
+        if active_msg.is_none() {
+            *active_msg = Some(signed_msg.clone());
             // We considered this validator inactive until now, and didn't accept proposals that
             // didn't have them in the `inactive` field. Mark all relevant rounds as dirty so that
             // the next `update` call checks all proposals again.
@@ -1560,14 +1952,71 @@ impl<C: Context + 'static> Zug<C> {
             }
         }
         self.maybe_dirty_round_id = None;
+        if !self.deferred_proposals.is_empty() {
+            outcomes.extend(self.handle_deferred_proposals(now));
+        }
+        outcomes
+    }
+
+    /// Handles the deferred proposals whose rounds are not in the future anymore.
+    fn handle_deferred_proposals(&mut self, now: Timestamp) -> ProtocolOutcomes<C> {
+        let future_proposals = self
+            .deferred_proposals
+            .split_off(&self.current_round.saturating_add(1));
+        let due_proposals = mem::replace(&mut self.deferred_proposals, future_proposals);
+        let mut outcomes = vec![];
+        for (round_id, proposals) in due_proposals {
+            self.deferred_proposal_count =
+                self.deferred_proposal_count.saturating_sub(proposals.len());
+            for (proposal, echo, sender) in proposals.into_values() {
+                debug!(
+                    our_idx = self.our_idx(),
+                    %sender,
+                    %proposal,
+                    %round_id,
+                    "handling deferred proposal with echo"
+                );
+                outcomes.extend(self.handle_signed_message(echo, sender, now));
+                outcomes.extend(self.handle_proposal(round_id, proposal, sender, now));
+            }
+        }
         outcomes
     }
 
+    /// Returns whether `echo` is an echo of the proposal, signed by the leader of the given round.
+    fn is_signed_leader_echo(
+        &self,
+        round_id: RoundId,
+        proposal: &Proposal<C>,
+        echo: &SignedMessage<C>,
+    ) -> bool {
+        if echo.round_id != round_id || echo.validator_idx != self.leader(round_id) {
+            return false;
+        }
+        if echo.content != Content::Echo(proposal.hash()) {
+            return false;
+        }
+        self.validators
+            .id(echo.validator_idx)
+            .map_or(false, |validator_id| echo.verify_signature(validator_id))
+    }
+
     /// Updates a round and sends appropriate messages.
     fn update_round(&mut self, round_id: RoundId, now: Timestamp) -> ProtocolOutcomes<C> {
         self.create_round(round_id);
         let mut outcomes = vec![];
 
+        // If a fault was detected before and this round hasn't been checked for new quorums yet,
+        // do it now.
+        if let Some(round_ids) = self.maybe_rescan_round_ids.clone() {
+            if round_ids.contains(&round_id) {
+                self.check_new_quorums(round_id);
+            }
+            if round_id >= *round_ids.end() {
+                self.maybe_rescan_round_ids = None;
+            }
+        }
+
         // If we have a proposal, echo it.
         if let Some(&hash) = self.rounds[&round_id].proposal().map(HashedProposal::hash) {
             outcomes.extend(self.create_and_gossip_message(round_id, Content::Echo(hash)));
@@ -1578,8 +2027,14 @@ impl<C: Context + 'static> Zug<C> {
             if round_id == self.current_round {
                 self.update_proposal_timeout(now);
             }
-            // Vote for finalizing this proposal.
-            outcomes.extend(self.create_and_gossip_message(round_id, Content::Vote(true)));
+            // Vote for finalizing this proposal, possibly after a delay.
+            if let Some(delay) = self.config.vote_delay {
+                let vote_time = now + delay;
+                self.delayed_votes.insert(round_id, vote_time);
+                outcomes.push(ProtocolOutcome::ScheduleTimer(vote_time, TIMER_ID_VOTE));
+            } else {
+                outcomes.extend(self.create_and_gossip_message(round_id, Content::Vote(true)));
+            }
             // Proposed descendants of this proposal can now be validated.
             if let Some(proposals) = self.proposals_waiting_for_parent.remove(&round_id) {
                 let ancestor_values = self
@@ -1592,6 +2047,7 @@ impl<C: Context + 'static> Zug<C> {
                             proposal.clone(),
                             ancestor_values.clone(),
                             sender,
+                            now,
                         ));
                     }
                 }
@@ -1646,7 +2102,7 @@ impl<C: Context + 'static> Zug<C> {
 
         // If the round has an accepted proposal and is committed, it is finalized.
         if self.has_accepted_proposal(round_id) && self.is_committed_round(round_id) {
-            outcomes.extend(self.finalize_round(round_id));
+            outcomes.extend(self.finalize_round(round_id, now));
         }
         outcomes
     }
@@ -1714,6 +2170,7 @@ impl<C: Context + 'static> Zug<C> {
         proposal: HashedProposal<C>,
         ancestor_values: Vec<C::ConsensusValue>,
         sender: NodeId,
+        now: Timestamp,
     ) -> ProtocolOutcomes<C> {
         let our_idx = self.our_idx();
         if proposal.timestamp() < self.params.start_timestamp() {
@@ -1723,6 +2180,35 @@ impl<C: Context + 'static> Zug<C> {
             );
             return vec![];
         }
+        // All rounds between the parent and this one must be skippable for the proposal to be
+        // accepted. If one of them is already committed, e.g. because it was finalized with a
+        // different proposal, the leader referenced a stale parent.
+        let first_skipped_round_id = proposal
+            .maybe_parent_round_id()
+            .map_or(0, |parent_round_id| parent_round_id.saturating_add(1));
+        if self
+            .rounds
+            .range(first_skipped_round_id..round_id)
+            .any(|(_, round)| round.quorum_votes() == Some(true))
+        {
+            info!(our_idx, "rejecting proposal that skips a committed round");
+            return vec![];
+        }
+        if let Some(parent_round_id) = proposal.maybe_parent_round_id() {
+            // After the switch block, only dummy proposals without a block are allowed. Blocks
+            // beyond the era's end height are fine as long as the era's minimum duration hasn't
+            // passed yet; `accepted_switch_block` takes both into account.
+            if proposal.maybe_block().is_some()
+                && (self.accepted_switch_block(parent_round_id)
+                    || self.accepted_dummy_proposal(parent_round_id))
+            {
+                info!(
+                    our_idx,
+                    "rejecting proposal with a block after the switch block"
+                );
+                return vec![];
+            }
+        }
         if let Some((_, parent_proposal)) = proposal
             .maybe_parent_round_id()
             .and_then(|parent_round_id| self.accepted_proposal(parent_round_id))
@@ -1768,7 +2254,10 @@ impl<C: Context + 'static> Zug<C> {
             }
         } else {
             self.log_proposal(&proposal, round_id, "proposal does not need validation");
-            if self.round_mut(round_id).insert_proposal(proposal.clone()) {
+            if self
+                .round_mut(round_id)
+                .insert_proposal(proposal.clone(), now)
+            {
                 self.record_entry(&Entry::Proposal(proposal.inner().clone(), round_id));
                 self.progress_detected = true;
                 self.mark_dirty(round_id);
@@ -1783,7 +2272,7 @@ impl<C: Context + 'static> Zug<C> {
 
     /// Finalizes the round, notifying the rest of the node of the finalized block
     /// if it contained one.
-    fn finalize_round(&mut self, round_id: RoundId) -> ProtocolOutcomes<C> {
+    fn finalize_round(&mut self, round_id: RoundId, now: Timestamp) -> ProtocolOutcomes<C> {
         let mut outcomes = vec![];
         if round_id < self.first_non_finalized_round_id {
             return outcomes; // This round was already finalized.
@@ -1801,7 +2290,7 @@ impl<C: Context + 'static> Zug<C> {
         };
         if let Some(parent_round_id) = proposal.maybe_parent_round_id() {
             // Output the parent first if it isn't already finalized.
-            outcomes.extend(self.finalize_round(parent_round_id));
+            outcomes.extend(self.finalize_round(parent_round_id, now));
         }
         for prune_round_id in self.first_non_finalized_round_id..round_id {
             info!(
@@ -1812,6 +2301,13 @@ impl<C: Context + 'static> Zug<C> {
             self.round_mut(prune_round_id).prune_skipped();
         }
         self.first_non_finalized_round_id = round_id.saturating_add(1);
+        if let Some(proposal_time) = self.round(round_id).and_then(Round::proposal_time) {
+            if self.finalization_latencies.len() >= MAX_FINALIZATION_LATENCIES {
+                self.finalization_latencies.pop_front();
+            }
+            self.finalization_latencies
+                .push_back((round_id, now.saturating_diff(proposal_time)));
+        }
         let value = if let Some(block) = proposal.maybe_block() {
             block.clone()
         } else {
@@ -1876,7 +2372,7 @@ impl<C: Context + 'static> Zug<C> {
                     || self.accepted_dummy_proposal(parent_round_id) =>
             {
                 // One of the ancestors is the switch block, so this proposal has no block.
-                return self.create_echo_and_proposal(Proposal::dummy(now, parent_round_id));
+                return self.create_echo_and_proposal(Proposal::dummy(now, parent_round_id), now);
             }
             Some(parent_round_id) => self
                 .ancestor_values(parent_round_id)
@@ -1895,7 +2391,11 @@ impl<C: Context + 'static> Zug<C> {
 
     /// Creates a new proposal message in the current round, and a corresponding signed echo,
     /// inserts them into our protocol state and gossips them.
-    fn create_echo_and_proposal(&mut self, proposal: Proposal<C>) -> ProtocolOutcomes<C> {
+    fn create_echo_and_proposal(
+        &mut self,
+        proposal: Proposal<C>,
+        now: Timestamp,
+    ) -> ProtocolOutcomes<C> {
         let round_id = self.current_round;
         let hashed_prop = HashedProposal::new(proposal.clone());
         let echo_content = Content::Echo(*hashed_prop.hash());
@@ -1916,8 +2416,9 @@ impl<C: Context + 'static> Zug<C> {
                 "could not record own proposal in WAL"
             );
             vec![]
-        } else if self.round_mut(round_id).insert_proposal(hashed_prop) {
+        } else if self.round_mut(round_id).insert_proposal(hashed_prop, now) {
             self.mark_dirty(round_id);
+            self.stats.proposals = self.stats.proposals.saturating_add(1);
             vec![ProtocolOutcome::CreatedGossipMessage(
                 SerializedMessage::from_message(&prop_msg),
             )]
@@ -2088,6 +2589,324 @@ impl<C: Context + 'static> Zug<C> {
             self.maybe_dirty_round_id = Some(round_id);
         }
     }
+
+    /// Adds the messages among the given outcomes to our statistics.
+    fn count_outcomes(&mut self, outcomes: &[ProtocolOutcome<C>]) {
+        for outcome in outcomes {
+            let counter = match outcome {
+                ProtocolOutcome::CreatedGossipMessage(_) => &mut self.stats.gossip_messages,
+                ProtocolOutcome::CreatedTargetedMessage(..) => &mut self.stats.targeted_messages,
+                ProtocolOutcome::CreatedMessageToRandomPeer(_) => {
+                    &mut self.stats.messages_to_random_peer
+                }
+                ProtocolOutcome::CreatedRequestToRandomPeer(_) => &mut self.stats.sync_requests,
+                _ => continue,
+            };
+            *counter = counter.saturating_add(1);
+        }
+    }
+}
+
+// Queries about the protocol state, for the consensus dump of the diagnostics port.
+impl<C: Context + 'static> Zug<C> {
+    /// Returns the round the protocol is currently in.
+    pub(crate) fn current_round(&self) -> RoundId {
+        self.current_round
+    }
+
+    /// Returns the non-finalized rounds that have an accepted proposal but no quorum of votes yet.
+    /// These are the rounds for which we need to collect more `true` votes to finalize a block.
+    pub(crate) fn rounds_pending_finalization(&self) -> Vec<RoundId> {
+        self.rounds
+            .range(self.first_non_finalized_round_id..)
+            .filter(|(_, round)| {
+                round.accepted_proposal().is_some() && round.quorum_votes().is_none()
+            })
+            .map(|(round_id, _)| *round_id)
+            .collect()
+    }
+
+    /// Returns the era's scheduled start timestamp and the minimum timestamp of its last block.
+    pub(crate) fn era_window(&self) -> (Timestamp, Timestamp) {
+        (self.params.start_timestamp(), self.params.end_timestamp())
+    }
+
+    /// Returns all block values we currently hold, in proposals in the retained rounds or in
+    /// proposals that are waiting for validation, without duplicates.
+    pub(crate) fn known_block_values(&self) -> Vec<&C::ConsensusValue> {
+        let round_values = self
+            .rounds
+            .values()
+            .filter_map(Round::proposal)
+            .filter_map(HashedProposal::maybe_block);
+        let validation_values = self
+            .proposals_waiting_for_validation
+            .keys()
+            .map(ProposedBlock::value);
+        round_values.chain(validation_values).unique().collect()
+    }
+
+    /// Returns the additional weight of `true` votes needed to commit the round, if it has an
+    /// accepted proposal but no quorum of votes yet. Faulty validators count towards the quorum.
+    pub(crate) fn votes_needed(&self, round_id: RoundId) -> Option<Weight> {
+        let round = self.round(round_id)?;
+        if round.accepted_proposal().is_none() || round.quorum_votes().is_some() {
+            return None;
+        }
+        let vote_weight: Weight = round
+            .votes(true)
+            .keys_some()
+            .filter(|vidx| !self.faults.contains_key(vidx))
+            .map(|vidx| self.validators.weight(vidx))
+            .sum();
+        let weight = self.faulty_weight().saturating_add(vote_weight);
+        let quorum_weight = self.quorum_threshold().saturating_add(Weight(1));
+        Some(Weight(quorum_weight.0.saturating_sub(weight.0)))
+    }
+
+    /// Returns the honest validators that haven't signed any echo or vote in any of the retained
+    /// rounds, and haven't led any round with an accepted proposal.
+    pub(crate) fn never_participated(&self) -> Vec<ValidatorIndex> {
+        self.validators
+            .enumerate_ids()
+            .map(|(idx, _)| idx)
+            .filter(|idx| !self.faults.contains_key(idx))
+            .filter(|idx| {
+                !self.rounds.values().any(|round| {
+                    round.has_echoed(*idx)
+                        || round.has_voted(*idx)
+                        || (round.accepted_proposal().is_some() && round.leader() == *idx)
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the weight of the honest validators that echoed the most-echoed proposal hash in
+    /// the round, plus the faulty weight, or `None` if there are no echoes in that round.
+    pub(crate) fn max_echo_weight(&self, round_id: RoundId) -> Option<Weight> {
+        let max_weight = self
+            .round(round_id)?
+            .echoes()
+            .values()
+            .map(|echo_map| {
+                self.sum_weights(
+                    echo_map
+                        .keys()
+                        .filter(|vidx| !self.faults.contains_key(vidx)),
+                )
+            })
+            .max()?;
+        Some(self.faulty_weight().saturating_add(max_weight))
+    }
+
+    /// Returns the fault tolerance threshold: if the total weight of faulty validators exceeds
+    /// this, the protocol stops with `FttExceeded`.
+    pub(crate) fn fault_tolerance_threshold_weight(&self) -> Weight {
+        self.params.ftt()
+    }
+
+    /// Returns the total weight of validators currently known to be faulty, including the ones
+    /// that were banned at the start of the era.
+    pub(crate) fn current_faulty_weight(&self) -> Weight {
+        self.faulty_weight()
+    }
+
+    /// Returns the non-finalized rounds that would get a new quorum of echoes or votes if the
+    /// given validator were marked as faulty. This is a dry run and doesn't modify the state.
+    pub(crate) fn quorum_changes_from_fault(&self, validator_idx: ValidatorIndex) -> Vec<RoundId> {
+        if self.faults.contains_key(&validator_idx) {
+            return vec![]; // Already faulty: nothing would change.
+        }
+        self.rounds
+            .range(self.first_non_finalized_round_id..)
+            .filter(|(_, round)| {
+                let new_echo_quorum = round.quorum_echoes().is_none()
+                    && round.echoes().values().any(|echo_map| {
+                        self.is_quorum_if_faulty(echo_map.keys().copied(), validator_idx)
+                    });
+                let new_vote_quorum = round.quorum_votes().is_none()
+                    && [true, false].into_iter().any(|vote| {
+                        self.is_quorum_if_faulty(round.votes(vote).keys_some(), validator_idx)
+                    });
+                new_echo_quorum || new_vote_quorum
+            })
+            .map(|(round_id, _)| *round_id)
+            .collect()
+    }
+
+    /// Returns whether the validators form a quorum, assuming `faulty_idx` were faulty, too.
+    fn is_quorum_if_faulty(
+        &self,
+        vidxs: impl Iterator<Item = ValidatorIndex>,
+        faulty_idx: ValidatorIndex,
+    ) -> bool {
+        let faulty_weight = self
+            .faulty_weight()
+            .saturating_add(self.validators.weight(faulty_idx));
+        let honest_weight: Weight = vidxs
+            .filter(|vidx| *vidx != faulty_idx && !self.faults.contains_key(vidx))
+            .map(|vidx| self.validators.weight(vidx))
+            .sum();
+        faulty_weight.saturating_add(honest_weight) > self.quorum_threshold()
+    }
+
+    /// Returns whether the validator can ever be the leader in this era. This is `false` for
+    /// validators that were banned or inactive at the start of the era, or have no weight.
+    pub(crate) fn can_propose(&self, idx: ValidatorIndex) -> bool {
+        self.leader_sequence.can_propose(idx)
+    }
+
+    /// Returns the round ID of each accepted proposal in the retained rounds, by its height
+    /// relative to the first block in this era.
+    ///
+    /// Heights are contiguous, while rounds that were skipped don't appear. If there are accepted
+    /// proposals in several rounds with the same height, the latest round is used.
+    pub(crate) fn height_to_round(&self) -> BTreeMap<u64, RoundId> {
+        self.rounds
+            .iter()
+            .filter_map(|(round_id, round)| {
+                let (height, _) = round.accepted_proposal()?;
+                Some((height, *round_id))
+            })
+            .collect()
+    }
+
+    /// Returns the block of the proposal accepted in the given round, or `None` if there is no
+    /// accepted proposal or it is a dummy proposal without a block.
+    pub(crate) fn accepted_block_value(&self, round_id: RoundId) -> Option<C::ConsensusValue> {
+        let (_, proposal) = self.accepted_proposal(round_id)?;
+        proposal.maybe_block().cloned()
+    }
+
+    /// Returns the round ID, relative height, timestamp and proposer of the latest finalized
+    /// block, i.e. of the latest finalized proposal that is not a dummy.
+    pub(crate) fn latest_finalized(&self) -> Option<(RoundId, u64, Timestamp, C::ValidatorId)> {
+        let mut round_id = self.first_non_finalized_round_id.checked_sub(1)?;
+        loop {
+            let (height, proposal) = self.accepted_proposal(round_id)?;
+            if proposal.maybe_block().is_some() {
+                let proposer = self.validators.id(self.leader(round_id))?.clone();
+                return Some((round_id, height, proposal.timestamp(), proposer));
+            }
+            round_id = proposal.maybe_parent_round_id()?;
+        }
+    }
+
+    /// Returns the minimum weight of correct validators in the intersection of any two quorums.
+    ///
+    /// Each quorum has a weight greater than the quorum threshold, so any two of them overlap in
+    /// at least `2 * (quorum_threshold + 1) - total_weight`. Subtracting the weight of validators
+    /// that may be faulty, i.e. the FTT or the known faulty weight if that is greater, leaves the
+    /// weight that is guaranteed to be correct. Safety requires this to be positive.
+    pub(crate) fn quorum_intersection_margin(&self) -> Weight {
+        let min_quorum_weight = u128::from(self.quorum_threshold().0).saturating_add(1);
+        let total_weight = u128::from(self.validators.total_weight().0);
+        let max_faulty_weight = u128::from(self.params.ftt().max(self.faulty_weight()).0);
+        let margin = min_quorum_weight
+            .saturating_mul(2)
+            .saturating_sub(total_weight)
+            .saturating_sub(max_faulty_weight);
+        Weight(u64::try_from(margin).unwrap_or(u64::MAX))
+    }
+
+    /// Returns the validator participation that is periodically logged, as numeric values that
+    /// can be exported as metrics.
+    pub(crate) fn participation_metrics(&self) -> ParticipationMetrics {
+        let participation = self.participation();
+        ParticipationMetrics {
+            inactive_stake_percent: participation.inactive_stake_percent,
+            faulty_stake_percent: participation.faulty_stake_percent,
+            inactive_validator_count: participation.inactive_validators.len() as u64,
+            faulty_validator_count: participation.faulty_validators.len() as u64,
+            current_round: self.current_round,
+        }
+    }
+
+    /// Returns what the current round is waiting for before the protocol can move on to the next
+    /// one.
+    pub(crate) fn current_round_blocker(&self) -> RoundBlocker {
+        let round = match self.round(self.current_round) {
+            Some(round) => round,
+            None => return RoundBlocker::NoProposal,
+        };
+        let maybe_proposal_hash = round.proposal().map(HashedProposal::hash);
+        if maybe_proposal_hash.is_some() && maybe_proposal_hash.copied() == round.quorum_echoes() {
+            RoundBlocker::AwaitingParentAcceptance
+        } else if round.votes(false).keys_some().next().is_some() {
+            RoundBlocker::AwaitingVotes
+        } else if maybe_proposal_hash.is_some() {
+            RoundBlocker::AwaitingEchoQuorum
+        } else {
+            RoundBlocker::NoProposal
+        }
+    }
+
+    /// Returns the number of outgoing messages this instance has created so far, by kind, and the
+    /// number of pruned and retained rounds.
+    pub(crate) fn protocol_stats(&self) -> ProtocolStats {
+        ProtocolStats {
+            retained_rounds: self.rounds.len() as u64,
+            ..self.stats
+        }
+    }
+
+    /// Returns the time from receiving to finalizing the proposal, for the most recent finalized
+    /// rounds in which we received the proposal, in the order in which they were finalized.
+    pub(crate) fn finalization_latencies(&self) -> Vec<(RoundId, TimeDiff)> {
+        self.finalization_latencies.iter().copied().collect()
+    }
+
+    /// Returns a certificate proving that the proposal in the given round was finalized, or `None`
+    /// if we don't have quorums of echoes and `true` votes in that round.
+    pub(crate) fn finality_certificate(&self, round_id: RoundId) -> Option<FinalityCertificate<C>> {
+        let round = self.round(round_id)?;
+        if round.quorum_votes() != Some(true) {
+            return None;
+        }
+        let proposal_hash = round.quorum_echoes()?;
+        Some(FinalityCertificate {
+            round_id,
+            instance_id: *self.instance_id(),
+            proposal_hash,
+            echoes: round.echoes().get(&proposal_hash)?.clone(),
+            true_votes: round
+                .votes(true)
+                .iter_some()
+                .map(|(vidx, signature)| (vidx, *signature))
+                .collect(),
+        })
+    }
+
+    /// Returns whether the certificate belongs to this protocol instance, all its signatures are
+    /// valid, and both the echoes and the `true` votes form a quorum.
+    pub(crate) fn verify_finality_certificate(&self, cert: &FinalityCertificate<C>) -> bool {
+        if cert.instance_id != *self.instance_id() {
+            return false;
+        }
+        let echoes = cert
+            .echoes
+            .iter()
+            .map(|(vidx, signature)| (Content::Echo(cert.proposal_hash), *vidx, *signature));
+        let true_votes = cert
+            .true_votes
+            .iter()
+            .map(|(vidx, signature)| (Content::Vote(true), *vidx, *signature));
+        for (content, validator_idx, signature) in echoes.chain(true_votes) {
+            let signed_msg = SignedMessage {
+                round_id: cert.round_id,
+                instance_id: cert.instance_id,
+                content,
+                validator_idx,
+                signature,
+            };
+            match self.validators.id(validator_idx) {
+                Some(validator_id) if signed_msg.verify_signature(validator_id) => {}
+                _ => return false,
+            }
+        }
+        self.is_quorum(cert.echoes.keys().copied())
+            && self.is_quorum(cert.true_votes.keys().copied())
+    }
 }
 
 impl<C> ConsensusProtocol<C> for Zug<C>
@@ -2102,7 +2921,7 @@ where
         now: Timestamp,
     ) -> ProtocolOutcomes<C> {
         let our_idx = self.our_idx();
-        match msg.deserialize_incoming() {
+        let outcomes = match msg.deserialize_incoming() {
             Err(err) => {
                 warn!(%sender, %err, "failed to deserialize Zug message");
                 vec![ProtocolOutcome::Disconnect(sender)]
@@ -2121,6 +2940,42 @@ where
                 proposal,
                 echo,
             }) => {
+                if self.config.defer_future_proposals
+                    && round_id > self.current_round
+                    && round_id <= self.current_round.saturating_add(MAX_FUTURE_ROUNDS)
+                    && self.deferred_proposal_count < MAX_DEFERRED_PROPOSALS
+                    && self.is_signed_leader_echo(round_id, &proposal, &echo)
+                {
+                    match self
+                        .deferred_proposals
+                        .entry(round_id)
+                        .or_default()
+                        .entry(echo.validator_idx)
+                    {
+                        btree_map::Entry::Occupied(_) => {
+                            debug!(
+                                our_idx,
+                                %sender,
+                                %proposal,
+                                %round_id,
+                                "already deferred a proposal for this round; dropping"
+                            );
+                        }
+                        btree_map::Entry::Vacant(entry) => {
+                            debug!(
+                                our_idx,
+                                %sender,
+                                %proposal,
+                                %round_id,
+                                "deferring future proposal"
+                            );
+                            entry.insert((proposal, echo, sender));
+                            self.deferred_proposal_count =
+                                self.deferred_proposal_count.saturating_add(1);
+                        }
+                    }
+                    return vec![];
+                }
                 // TODO: make sure that `echo` is indeed an echo
                 debug!(our_idx, %sender, %proposal, %round_id, "handling proposal with echo");
                 let mut outcomes = self.handle_signed_message(echo, sender, now);
@@ -2131,16 +2986,21 @@ where
             Ok(Message::Evidence(signed_msg, content2, signature2)) => {
                 self.handle_evidence(signed_msg, content2, signature2, sender, now)
             }
-        }
+            Ok(Message::FinalityCertificate(cert)) => {
+                self.handle_finality_certificate(cert, sender, now)
+            }
+        };
+        self.count_outcomes(&outcomes);
+        outcomes
     }
 
     /// Handles an incoming request message and returns an optional response.
     fn handle_request_message(
         &mut self,
-        _rng: &mut NodeRng,
+        rng: &mut NodeRng,
         sender: NodeId,
         msg: SerializedMessage,
-        _now: Timestamp,
+        now: Timestamp,
     ) -> (ProtocolOutcomes<C>, Option<SerializedMessage>) {
         let our_idx = self.our_idx();
         match msg.deserialize_incoming::<SyncRequest<C>>() {
@@ -2158,7 +3018,18 @@ where
                 warn!(our_idx, ?instance_id, %sender, "wrong instance ID; disconnecting");
                 (vec![ProtocolOutcome::Disconnect(sender)], None)
             }
-            Ok(sync_request) => self.handle_sync_request(sync_request, sender),
+            Ok(sync_request) => {
+                let round_id = sync_request.round_id;
+                let (mut outcomes, maybe_response) = self.handle_sync_request(sync_request, sender);
+                if round_id > self.current_round && self.round(round_id).is_none() {
+                    outcomes.extend(self.handle_sync_request_from_future(round_id, rng, now));
+                }
+                self.count_outcomes(&outcomes);
+                if maybe_response.is_some() {
+                    self.stats.sync_responses = self.stats.sync_responses.saturating_add(1);
+                }
+                (outcomes, maybe_response)
+            }
         }
     }
 
@@ -2170,7 +3041,7 @@ where
         timer_id: TimerId,
         rng: &mut NodeRng,
     ) -> ProtocolOutcomes<C> {
-        match timer_id {
+        let outcomes = match timer_id {
             TIMER_ID_SYNC_PEER => self.handle_sync_peer_timer(now, rng),
             TIMER_ID_UPDATE => {
                 if timestamp >= self.next_scheduled_update {
@@ -2179,6 +3050,7 @@ where
                 self.mark_dirty(self.current_round);
                 self.update(now)
             }
+            TIMER_ID_VOTE => self.handle_vote_timer(now),
             TIMER_ID_LOG_PARTICIPATION => {
                 self.log_participation();
                 match self.config.log_participation_interval {
@@ -2188,6 +3060,7 @@ where
                     _ => vec![],
                 }
             }
+            TIMER_ID_MISSING_PROPOSALS => self.handle_missing_proposals_timer(now, rng),
             // TIMER_ID_VERTEX_WITH_FUTURE_TIMESTAMP => {
             //     self.synchronizer.add_past_due_stored_vertices(now)
             // }
@@ -2199,7 +3072,9 @@ where
                 );
                 vec![]
             }
-        }
+        };
+        self.count_outcomes(&outcomes);
+        outcomes
     }
 
     fn handle_is_current(&self, now: Timestamp) -> ProtocolOutcomes<C> {
@@ -2209,6 +3084,10 @@ where
                 now.max(self.params.start_timestamp()) + interval,
                 TIMER_ID_SYNC_PEER,
             ));
+            outcomes.push(ProtocolOutcome::ScheduleTimer(
+                now.max(self.params.start_timestamp()) + interval,
+                TIMER_ID_MISSING_PROPOSALS,
+            ));
         }
         if let Some(interval) = self.config.log_participation_interval {
             outcomes.push(ProtocolOutcome::ScheduleTimer(
@@ -2244,8 +3123,9 @@ where
             .map(|(idx, _)| idx)
             .filter(|idx| self.active[*idx].is_none() && !self.faults.contains_key(idx));
         let proposal = Proposal::with_block(&proposed_block, maybe_parent_round_id, inactive);
-        let mut outcomes = self.create_echo_and_proposal(proposal);
+        let mut outcomes = self.create_echo_and_proposal(proposal, now);
         outcomes.extend(self.update(now));
+        self.count_outcomes(&outcomes);
         outcomes
     }
 
@@ -2262,9 +3142,15 @@ where
             .flatten();
         let mut outcomes = vec![];
         if valid {
-            for (round_id, proposal, _sender) in rounds_and_node_ids {
+            // The same proposal may have been received from several senders. Handle it only once.
+            let unique_proposals = rounds_and_node_ids
+                .unique_by(|(round_id, proposal, _sender)| (*round_id, *proposal.hash()));
+            for (round_id, proposal, _sender) in unique_proposals {
                 info!(our_idx = self.our_idx(), %round_id, %proposal, "handling valid proposal");
-                if self.round_mut(round_id).insert_proposal(proposal.clone()) {
+                if self
+                    .round_mut(round_id)
+                    .insert_proposal(proposal.clone(), now)
+                {
                     self.record_entry(&Entry::Proposal(proposal.into_inner(), round_id));
                     self.mark_dirty(round_id);
                     self.progress_detected = true;
@@ -2290,6 +3176,7 @@ where
                 );
             }
         }
+        self.count_outcomes(&outcomes);
         outcomes
     }
 
@@ -2339,9 +3226,13 @@ where
 
     fn set_evidence_only(&mut self) {
         self.evidence_only = true;
+        let pruned_rounds = self.rounds.len() as u64;
+        self.stats.pruned_rounds = self.stats.pruned_rounds.saturating_add(pruned_rounds);
         self.rounds.clear();
         self.proposals_waiting_for_parent.clear();
         self.proposals_waiting_for_validation.clear();
+        self.deferred_proposals.clear();
+        self.deferred_proposal_count = 0;
     }
 
     fn has_evidence(&self, vid: &C::ValidatorId) -> bool {
@@ -2385,7 +3276,9 @@ where
             // Reset the timeout to give the proposer another chance, after the pause.
             self.current_round_start = Timestamp::MAX;
             self.mark_dirty(self.current_round);
-            self.update(now)
+            let outcomes = self.update(now);
+            self.count_outcomes(&outcomes);
+            outcomes
         } else {
             if self.paused != paused {
                 info!(
@@ -2399,6 +3292,69 @@ where
         }
     }
 
+    /// This doesn't affect proposing, echoing and voting. While disabled, the sync timer keeps
+    /// getting rescheduled, so that syncing resumes at the usual interval once it is enabled again.
+    fn set_sync_enabled(&mut self, enabled: bool) {
+        if self.sync_enabled != enabled {
+            info!(
+                our_idx = self.our_idx(),
+                enabled, "changing whether to sync with random peers"
+            );
+        }
+        self.sync_enabled = enabled;
+    }
+
+    /// Makes `round_id` the current round and starts its proposal timeout now, without waiting
+    /// for the earlier rounds to be skipped or to get an accepted proposal. If we are the leader
+    /// and a suitable parent is available, we propose right away.
+    ///
+    /// In normal operation rounds are started by `update_round`.
+    fn force_start_round(&mut self, round_id: RoundId, now: Timestamp) -> ProtocolOutcomes<C> {
+        let our_idx = self.our_idx();
+        if self.evidence_only {
+            warn!(
+                our_idx,
+                round_id, "not force-starting a round in evidence-only mode"
+            );
+            return vec![];
+        }
+        if round_id < self.current_round {
+            warn!(
+                our_idx,
+                round_id,
+                current_round = self.current_round,
+                "not force-starting a round earlier than the current one"
+            );
+            return vec![];
+        }
+        // Rounds that were waiting to be rescanned after a fault won't become current one by one
+        // anymore, so the next update needs to check all of them.
+        let maybe_first_rescan_round_id = self
+            .maybe_rescan_round_ids
+            .as_ref()
+            .map(|round_ids| (*round_ids.start()).max(self.current_round));
+        self.create_round(round_id);
+        self.current_round = round_id;
+        self.current_round_start = now;
+        if let Some(first_rescan_round_id) = maybe_first_rescan_round_id {
+            self.mark_dirty(first_rescan_round_id);
+        }
+        info!(
+            our_idx,
+            round_id,
+            leader = self.leader(round_id).0,
+            "force-started a new round"
+        );
+        let mut outcomes = vec![];
+        if let Some((maybe_parent_round_id, timestamp)) = self.suitable_parent_round(now) {
+            if now >= timestamp {
+                outcomes.extend(self.propose_if_leader(maybe_parent_round_id, now));
+            }
+        }
+        outcomes.extend(self.schedule_update(now.saturating_add(self.proposal_timeout())));
+        outcomes
+    }
+
     fn validators_with_evidence(&self) -> Vec<&C::ValidatorId> {
         self.faults
             .iter()
@@ -2419,6 +3375,14 @@ where
         self.params.instance_id()
     }
 
+    fn validator_count(&self) -> usize {
+        self.validators.len()
+    }
+
+    fn total_weight(&self) -> Weight {
+        self.validators.total_weight()
+    }
+
     fn next_round_length(&self) -> Option<TimeDiff> {
         Some(self.params.min_block_time())
     }
@@ -2437,8 +3401,8 @@ mod specimen_support {
 
     use super::{
         message::{
-            Content, ContentDiscriminants, Message, MessageDiscriminants, SignedMessage,
-            SyncResponse,
+            Content, ContentDiscriminants, FinalityCertificate, Message, MessageDiscriminants,
+            SignedMessage, SyncResponse,
         },
         proposal::Proposal,
         SyncRequest,
@@ -2466,6 +3430,9 @@ mod specimen_support {
                         LargestSpecimen::largest_specimen(estimator, cache),
                         LargestSpecimen::largest_specimen(estimator, cache),
                     ),
+                    MessageDiscriminants::FinalityCertificate => Message::FinalityCertificate(
+                        LargestSpecimen::largest_specimen(estimator, cache),
+                    ),
                 },
             )
         }
@@ -2518,6 +3485,18 @@ mod specimen_support {
         }
     }
 
+    impl LargestSpecimen for FinalityCertificate<ClContext> {
+        fn largest_specimen<E: SizeEstimator>(estimator: &E, cache: &mut Cache) -> Self {
+            FinalityCertificate {
+                round_id: LargestSpecimen::largest_specimen(estimator, cache),
+                instance_id: LargestSpecimen::largest_specimen(estimator, cache),
+                proposal_hash: LargestSpecimen::largest_specimen(estimator, cache),
+                echoes: btree_map_distinct_from_prop(estimator, "validator_count", cache),
+                true_votes: btree_map_distinct_from_prop(estimator, "validator_count", cache),
+            }
+        }
+    }
+
     impl LargestSpecimen for Proposal<ClContext> {
         fn largest_specimen<E: SizeEstimator>(estimator: &E, cache: &mut Cache) -> Self {
             Proposal {
@@ -1,9 +1,23 @@
 use serde::{Deserialize, Serialize};
 
 use datasize::DataSize;
+use tracing::error;
 
 use casper_types::{serde_option_time_diff, TimeDiff};
 
+/// How to select the round about which we inform a random peer when syncing.
+#[derive(DataSize, Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncRoundStrategy {
+    /// A random round between the lowest non-finalized one and the current one.
+    #[default]
+    Random,
+    /// The lowest round that is not finalized yet.
+    LowestPending,
+    /// The current round.
+    Current,
+}
+
 /// `Zug`-specific configuration.
 /// *Note*: This is *not* protocol configuration that has to be the same on all nodes.
 #[derive(DataSize, Debug, Clone, Serialize, Deserialize)]
@@ -29,6 +43,25 @@ pub struct Config {
     pub proposal_timeout_inertia: u16,
     /// Incoming proposals whose timestamps lie further in the future are rejected.
     pub clock_tolerance: TimeDiff,
+    /// If `true`, proposals from future rounds are stored and only handled once their round
+    /// becomes current, instead of instantiating those rounds right away.
+    pub defer_future_proposals: bool,
+    /// When a validator turns out to be faulty, rounds up to this many rounds after the current
+    /// one are immediately checked for new quorums. Later rounds are checked once they become
+    /// current.
+    pub fault_rescan_window: u32,
+    /// After a proposal becomes accepted, we wait this long before voting `true`. 0 means we vote
+    /// immediately.
+    #[serde(with = "serde_option_time_diff")]
+    pub vote_delay: Option<TimeDiff>,
+    /// Which round to include in the periodic sync requests to a random peer.
+    pub sync_round_strategy: SyncRoundStrategy,
+    /// The maximum number of signed messages, i.e. echoes, votes, proofs of activity and evidence,
+    /// in a single sync response. If there are more, the requester has to ask again.
+    pub max_sync_response_messages: u32,
+    /// A validator who hasn't signed any message in the last this many rounds is reported as
+    /// inactive in the participation log.
+    pub participation_grace_rounds: u32,
 }
 
 impl Default for Config {
@@ -40,6 +73,24 @@ impl Default for Config {
             clock_tolerance: "1sec".parse().unwrap(),
             proposal_grace_period: 200,
             proposal_timeout_inertia: 10,
+            defer_future_proposals: false,
+            fault_rescan_window: 100,
+            vote_delay: None,
+            sync_round_strategy: SyncRoundStrategy::Random,
+            max_sync_response_messages: 1000,
+            participation_grace_rounds: 2,
+        }
+    }
+}
+
+impl Config {
+    /// Checks whether the values set in the config make sense and returns `false` if they don't.
+    pub(crate) fn is_valid(&self) -> bool {
+        if self.max_sync_response_messages == 0 {
+            error!("max_sync_response_messages must be positive");
+            return false;
         }
+
+        true
     }
 }
@@ -28,7 +28,7 @@ mod relaxed {
         traits::{ConsensusNetworkMessage, Context},
     };
 
-    use super::{SignedMessage, SyncResponse};
+    use super::{FinalityCertificate, SignedMessage, SyncResponse};
 
     /// The content of a message in the main protocol, as opposed to the proposal, and to sync
     /// messages, which are somewhat decoupled from the rest of the protocol. These messages,
@@ -88,6 +88,9 @@ mod relaxed {
         Signed(SignedMessage<C>),
         /// Two conflicting signatures by the same validator.
         Evidence(SignedMessage<C>, Content<C>, C::Signature),
+        /// Proof that a proposal was finalized, sent in response to a sync request instead of
+        /// individual echoes and votes.
+        FinalityCertificate(FinalityCertificate<C>),
     }
 
     impl<C: Context> ConsensusNetworkMessage for Message<C> {}
@@ -167,6 +170,10 @@ impl<C: Context> SignedMessage<C> {
     }
 
     /// Returns the hash of all fields except the signature.
+    ///
+    /// The serialized content starts with the variant index, which serves as a domain tag: A
+    /// signature over an echo (including the leader's echo that accompanies a proposal) is never
+    /// valid for a vote, and vice versa.
     fn hash_fields(
         round_id: RoundId,
         instance_id: &C::InstanceId,
@@ -285,7 +292,38 @@ impl<C: Context> Message<C> {
             Message::SyncResponse(SyncResponse { instance_id, .. })
             | Message::Signed(SignedMessage { instance_id, .. })
             | Message::Proposal { instance_id, .. }
-            | Message::Evidence(SignedMessage { instance_id, .. }, ..) => instance_id,
+            | Message::Evidence(SignedMessage { instance_id, .. }, ..)
+            | Message::FinalityCertificate(FinalityCertificate { instance_id, .. }) => instance_id,
         }
     }
+
+    /// Returns the length of the message's serialization as it is sent over the network, without
+    /// allocating a buffer for it.
+    #[cfg(test)]
+    pub(crate) fn serialized_size(&self) -> usize {
+        bincode::serialized_size(self).expect("should serialize message") as usize
+    }
+}
+
+/// Proof that a proposal was finalized: a quorum of echoes for the proposal's hash and a quorum of
+/// `true` votes, all signed in the same round.
+#[derive(DataSize, Clone, Serialize, Deserialize, Debug, PartialEq, Eq, Hash)]
+#[serde(bound(
+    serialize = "C::Hash: Serialize",
+    deserialize = "C::Hash: Deserialize<'de>",
+))]
+pub(crate) struct FinalityCertificate<C>
+where
+    C: Context,
+{
+    /// The round in which the proposal was finalized.
+    pub(crate) round_id: RoundId,
+    /// The protocol instance.
+    pub(crate) instance_id: C::InstanceId,
+    /// The hash of the finalized proposal.
+    pub(crate) proposal_hash: C::Hash,
+    /// The validators' signatures of echoes for `proposal_hash`.
+    pub(crate) echoes: BTreeMap<ValidatorIndex, C::Signature>,
+    /// The validators' signatures of `true` votes.
+    pub(crate) true_votes: BTreeMap<ValidatorIndex, C::Signature>,
 }
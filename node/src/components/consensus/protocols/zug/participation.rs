@@ -1,9 +1,11 @@
-use std::fmt::Debug;
+use std::{collections::BTreeMap, fmt::Debug};
+
+use serde::Serialize;
 
 use crate::components::consensus::{
     protocols::zug::{Fault, RoundId, Zug},
     traits::Context,
-    utils::ValidatorIndex,
+    utils::{ValidatorIndex, ValidatorMap},
 };
 
 /// A map of status (faulty, inactive) by validator ID.
@@ -21,6 +23,16 @@ where
     pub(super) faulty_validators: Vec<(ValidatorIndex, C::ValidatorId, ParticipationStatus)>,
 }
 
+/// The numeric values of a `Participation`, suitable as metrics gauges.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub(crate) struct ParticipationMetrics {
+    pub(crate) inactive_stake_percent: u8,
+    pub(crate) faulty_stake_percent: u8,
+    pub(crate) inactive_validator_count: u64,
+    pub(crate) faulty_validator_count: u64,
+    pub(crate) current_round: RoundId,
+}
+
 /// A validator's participation status: whether they are faulty or inactive.
 #[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq)]
 pub(super) enum ParticipationStatus {
@@ -31,30 +43,62 @@ pub(super) enum ParticipationStatus {
 }
 
 impl ParticipationStatus {
-    /// Returns a `Status` for a validator unless they are honest and online.
-    pub(super) fn for_index<C: Context + 'static>(
-        idx: ValidatorIndex,
+    /// Returns a `Status` for each validator, or `None` if they are honest and online.
+    ///
+    /// This only iterates over the rounds once, instead of once per validator.
+    pub(super) fn for_all<C: Context + 'static>(
         zug: &Zug<C>,
-    ) -> Option<ParticipationStatus> {
-        if let Some(fault) = zug.faults.get(&idx) {
-            return Some(match fault {
-                Fault::Banned | Fault::Indirect => ParticipationStatus::EquivocatedInOtherEra,
-                Fault::Direct(..) => ParticipationStatus::Equivocated,
-            });
-        }
-        // TODO: Avoid iterating over all old rounds every time we log this.
-        for r_id in zug.rounds.keys().rev() {
-            if zug.has_echoed(*r_id, idx)
-                || zug.has_voted(*r_id, idx)
-                || (zug.has_accepted_proposal(*r_id) && zug.leader(*r_id) == idx)
-            {
-                if r_id.saturating_add(2) < zug.current_round {
-                    return Some(ParticipationStatus::LastSeenInRound(*r_id));
-                } else {
-                    return None; // Seen recently; considered currently active.
+    ) -> ValidatorMap<Option<ParticipationStatus>> {
+        let mut last_seen: ValidatorMap<Option<RoundId>> = vec![None; zug.validators.len()].into();
+        let mut unseen_count = zug
+            .validators
+            .enumerate_ids()
+            .filter(|(idx, _)| !zug.faults.contains_key(idx))
+            .count();
+        for (r_id, round) in zug.rounds.iter().rev() {
+            if unseen_count == 0 {
+                break;
+            }
+            let echoes = round.echoes().values().flat_map(BTreeMap::keys).copied();
+            let votes = round
+                .votes(true)
+                .keys_some()
+                .chain(round.votes(false).keys_some());
+            let leader = zug.has_accepted_proposal(*r_id).then(|| zug.leader(*r_id));
+            for idx in echoes.chain(votes).chain(leader) {
+                if let Some(maybe_r_id) = last_seen.get_mut(idx) {
+                    if maybe_r_id.is_none() && !zug.faults.contains_key(&idx) {
+                        *maybe_r_id = Some(*r_id);
+                        unseen_count = unseen_count.saturating_sub(1);
+                    }
                 }
             }
         }
-        Some(ParticipationStatus::Inactive)
+        last_seen
+            .enumerate()
+            .map(|(idx, maybe_r_id)| {
+                if let Some(fault) = zug.faults.get(&idx) {
+                    return Some(Self::for_fault(fault));
+                }
+                match maybe_r_id {
+                    Some(r_id)
+                        if r_id.saturating_add(zug.config.participation_grace_rounds)
+                            < zug.current_round =>
+                    {
+                        Some(ParticipationStatus::LastSeenInRound(*r_id))
+                    }
+                    Some(_) => None, // Seen recently; considered currently active.
+                    None => Some(ParticipationStatus::Inactive),
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the `Status` of a validator with the given fault.
+    fn for_fault<C: Context>(fault: &Fault<C>) -> ParticipationStatus {
+        match fault {
+            Fault::Banned | Fault::Indirect => ParticipationStatus::EquivocatedInOtherEra,
+            Fault::Direct(..) => ParticipationStatus::Equivocated,
+        }
     }
 }
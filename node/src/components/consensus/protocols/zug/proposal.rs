@@ -78,6 +78,8 @@ where
 }
 
 impl<C: Context> HashedProposal<C> {
+    /// Creates a new proposal and computes its hash, from the bincode serialization. This relies
+    /// on the consensus value's serialization being deterministic.
     pub(crate) fn new(proposal: Proposal<C>) -> Self {
         let serialized = bincode::serialize(&proposal).expect("failed to serialize fields");
         let hash = <C as Context>::hash(&serialized);
@@ -6,6 +6,8 @@ use std::{
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
 
+use casper_types::Timestamp;
+
 use crate::{
     components::consensus::{
         protocols::zug::{Content, HashedProposal},
@@ -27,7 +29,12 @@ where
     leader_idx: ValidatorIndex,
     /// The unique proposal signed by the leader, or the unique proposal with a quorum of echoes.
     proposal: Option<HashedProposal<C>>,
+    /// The time when we received the current proposal.
+    proposal_time: Option<Timestamp>,
     /// The echoes we've received for each proposal so far.
+    ///
+    /// This can't grow unboundedly: A second echo from the same validator is an equivocation, and
+    /// all echoes from faulty validators are removed, so there is at most one hash per validator.
     #[data_size(with = ds::hashmap_sample)]
     echoes: HashMap<C::Hash, BTreeMap<ValidatorIndex, C::Signature>>,
     /// The votes we've received for this round so far.
@@ -46,6 +53,7 @@ impl<C: Context> Round<C> {
         Round {
             leader_idx,
             proposal: None,
+            proposal_time: None,
             echoes: HashMap::new(),
             votes,
             outcome: RoundOutcome::default(),
@@ -57,6 +65,11 @@ impl<C: Context> Round<C> {
         self.proposal.as_ref()
     }
 
+    /// Returns the time when we received the current proposal.
+    pub(super) fn proposal_time(&self) -> Option<Timestamp> {
+        self.proposal_time
+    }
+
     /// Returns whether we have received at least one proposal.
     pub(super) fn has_proposal(&self) -> bool {
         self.proposal.is_some()
@@ -74,10 +87,11 @@ impl<C: Context> Round<C> {
 
     /// Inserts a `Proposal` and returns `false` if we already had it or it cannot be added due to
     /// missing echoes.
-    pub(super) fn insert_proposal(&mut self, proposal: HashedProposal<C>) -> bool {
+    pub(super) fn insert_proposal(&mut self, proposal: HashedProposal<C>, now: Timestamp) -> bool {
         let hash = proposal.hash();
         if self.has_echoes_for_proposal(hash) && self.proposal.as_ref() != Some(&proposal) {
             self.proposal = Some(proposal);
+            self.proposal_time = Some(now);
             true
         } else {
             false
@@ -201,6 +215,7 @@ impl<C: Context> Round<C> {
     /// Removes the proposal: This round was skipped and will never become finalized.
     pub(super) fn prune_skipped(&mut self) {
         self.proposal = None;
+        self.proposal_time = None;
         self.outcome.accepted_proposal_height = None;
     }
 
@@ -14,13 +14,13 @@ use crate::{
         leader_sequence,
         protocols::common,
         tests::utils::{
-            new_test_chainspec, ALICE_NODE_ID, ALICE_PUBLIC_KEY, ALICE_SECRET_KEY, BOB_PUBLIC_KEY,
-            BOB_SECRET_KEY, CAROL_PUBLIC_KEY, CAROL_SECRET_KEY,
+            new_test_chainspec, ALICE_NODE_ID, ALICE_PUBLIC_KEY, ALICE_SECRET_KEY, BOB_NODE_ID,
+            BOB_PUBLIC_KEY, BOB_SECRET_KEY, CAROL_PUBLIC_KEY, CAROL_SECRET_KEY,
         },
         traits::Context,
     },
     testing,
-    types::BlockPayload,
+    types::{Approval, BlockPayload, DeployHash, DeployHashWithApprovals},
 };
 
 const INSTANCE_ID_DATA: &[u8; 1] = &[123u8; 1];
@@ -313,6 +313,35 @@ fn expect_timer(outcomes: &ProtocolOutcomes<ClContext>, timestamp: Timestamp, ti
     );
 }
 
+/// Checks that the sync peer timer is scheduled within the jitter bounds around `now + interval`,
+/// and returns its delay.
+fn expect_sync_timer(
+    outcomes: &ProtocolOutcomes<ClContext>,
+    now: Timestamp,
+    interval: TimeDiff,
+) -> TimeDiff {
+    let timestamp = outcomes
+        .iter()
+        .find_map(|outcome| match outcome {
+            ProtocolOutcome::ScheduleTimer(timestamp, TIMER_ID_SYNC_PEER) => Some(*timestamp),
+            _ => None,
+        })
+        .unwrap_or_else(|| panic!("missing sync peer timer from {:?}", outcomes));
+    let delay = timestamp.saturating_diff(now);
+    let min_delay =
+        TimeDiff::from_millis((interval.millis() as f64 * (1.0 - SYNC_PEER_JITTER)) as u64);
+    let max_delay =
+        TimeDiff::from_millis((interval.millis() as f64 * (1.0 + SYNC_PEER_JITTER)) as u64);
+    assert!(
+        min_delay <= delay && delay <= max_delay,
+        "sync peer timer delay {} not within [{}, {}]",
+        delay,
+        min_delay,
+        max_delay
+    );
+    delay
+}
+
 /// Creates a new payload with the given random bit and no deploys or transfers.
 fn new_payload(random_bit: bool) -> Arc<BlockPayload> {
     Arc::new(BlockPayload::new(vec![], vec![], vec![], random_bit))
@@ -647,7 +676,7 @@ fn zug_sends_sync_request() {
 
     // The protocol state is empty and the SyncRequest should reflect that.
     let mut outcomes = zug.handle_timer(timestamp, timestamp, TIMER_ID_SYNC_PEER, &mut rng);
-    expect_timer(&outcomes, timestamp + timeout, TIMER_ID_SYNC_PEER);
+    expect_sync_timer(&outcomes, timestamp, timeout);
     let mut msg_iter = remove_requests_to_random(&mut outcomes).into_iter();
     match (msg_iter.next(), msg_iter.next()) {
         (
@@ -682,7 +711,7 @@ fn zug_sends_sync_request() {
 
     // The next SyncRequest message must include all the new information.
     let mut outcomes = zug.handle_timer(timestamp, timestamp, TIMER_ID_SYNC_PEER, &mut rng);
-    expect_timer(&outcomes, timestamp + timeout, TIMER_ID_SYNC_PEER);
+    expect_sync_timer(&outcomes, timestamp, timeout);
     let mut msg_iter = remove_requests_to_random(&mut outcomes).into_iter();
     match (msg_iter.next(), msg_iter.next()) {
         (
@@ -1034,3 +1063,2432 @@ fn update_proposal_timeout() {
         );
     }
 }
+
+/// Tests that rounds with an accepted but uncommitted proposal are reported as pending
+/// finalization, and committed ones are not.
+#[test]
+fn zug_rounds_pending_finalization() {
+    let mut rng = crate::new_rng();
+    let (weights, validators) = abc_weights(60, 30, 10);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+    let carol_idx = validators.get_index(&*CAROL_PUBLIC_KEY).unwrap();
+
+    // Alice is the leader in rounds 0 and 1.
+    let mut zug = new_test_zug(weights, vec![], &[alice_idx, alice_idx]);
+
+    let alice_kp = Keypair::from(ALICE_SECRET_KEY.clone());
+    let bob_kp = Keypair::from(BOB_SECRET_KEY.clone());
+
+    let sender = *ALICE_NODE_ID;
+    let mut timestamp = Timestamp::from(100000);
+
+    let proposal0 = Proposal::<ClContext> {
+        timestamp,
+        maybe_block: Some(new_payload(false)),
+        maybe_parent_round_id: None,
+        inactive: None,
+    };
+    let hash0 = proposal0.hash();
+
+    timestamp += zug.params.min_block_time();
+
+    let proposal1 = Proposal::<ClContext> {
+        timestamp,
+        maybe_block: Some(new_payload(true)),
+        maybe_parent_round_id: Some(0),
+        inactive: Some(iter::once(carol_idx).collect()),
+    };
+    let hash1 = proposal1.hash();
+
+    assert!(zug.rounds_pending_finalization().is_empty());
+
+    // Alice proposes in round 0 and Bob echoes: The proposal is accepted but not committed.
+    let msg = create_proposal_message(0, &proposal0, &validators, &alice_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    let msg = create_message(&validators, 0, echo(hash0), &bob_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    assert_eq!(vec![0], zug.rounds_pending_finalization());
+
+    // Once Alice and Bob vote for it, round 0 is committed and not pending anymore.
+    let msg = create_message(&validators, 0, vote(true), &alice_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    let msg = create_message(&validators, 0, vote(true), &bob_kp);
+    let outcomes = zug.handle_message(&mut rng, sender, msg, timestamp);
+    expect_finalized(&outcomes, &[(&proposal0, 0)]);
+    assert!(zug.rounds_pending_finalization().is_empty());
+
+    // Alice's child proposal in round 1 gets accepted and is pending.
+    let msg = create_proposal_message(1, &proposal1, &validators, &alice_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    let msg = create_message(&validators, 1, echo(hash1), &bob_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    assert_eq!(vec![1], zug.rounds_pending_finalization());
+}
+
+/// Tests that the era window spans the configured era duration from the start timestamp.
+#[test]
+fn zug_era_window() {
+    let (weights, _validators) = abc_weights(60, 30, 10);
+    let mut chainspec = new_test_chainspec(weights.clone());
+    chainspec.core_config.era_duration = TimeDiff::from_seconds(3600);
+    let start_timestamp = Timestamp::from(100000);
+    let zug = Zug::<ClContext>::new(
+        ClContext::hash(INSTANCE_ID_DATA),
+        weights.into_iter().collect(),
+        &Default::default(),
+        &Default::default(),
+        &chainspec,
+        &Config::default(),
+        None,
+        start_timestamp,
+        0,
+    );
+    assert_eq!(
+        (
+            start_timestamp,
+            start_timestamp + TimeDiff::from_seconds(3600)
+        ),
+        zug.era_window()
+    );
+}
+
+/// Tests that a proposal is rejected if it skips over a round that has already been committed.
+#[test]
+fn zug_reject_proposal_skipping_committed_round() {
+    let mut rng = crate::new_rng();
+    let (weights, validators) = abc_weights(60, 30, 10);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+    let carol_idx = validators.get_index(&*CAROL_PUBLIC_KEY).unwrap();
+
+    // Alice is the leader in rounds 0, 1 and 2.
+    let mut zug = new_test_zug(weights, vec![], &[alice_idx, alice_idx, alice_idx]);
+
+    let alice_kp = Keypair::from(ALICE_SECRET_KEY.clone());
+    let bob_kp = Keypair::from(BOB_SECRET_KEY.clone());
+
+    let sender = *ALICE_NODE_ID;
+    let mut timestamp = Timestamp::from(100000);
+
+    let proposal0 = Proposal::<ClContext> {
+        timestamp,
+        maybe_block: Some(new_payload(false)),
+        maybe_parent_round_id: None,
+        inactive: None,
+    };
+    let hash0 = proposal0.hash();
+
+    timestamp += zug.params.min_block_time();
+
+    let proposal1 = Proposal::<ClContext> {
+        timestamp,
+        maybe_block: Some(new_payload(true)),
+        maybe_parent_round_id: Some(0),
+        inactive: Some(iter::once(carol_idx).collect()),
+    };
+    let hash1 = proposal1.hash();
+
+    // A proposal in round 2 that treats round 1 as skipped, although it was committed.
+    let stale_proposal2 = Proposal::<ClContext> {
+        timestamp,
+        maybe_block: Some(new_payload(false)),
+        maybe_parent_round_id: Some(0),
+        inactive: Some(iter::once(carol_idx).collect()),
+    };
+    let stale_hash2 = stale_proposal2.hash();
+
+    // Rounds 0 and 1 get finalized.
+    for (round_id, proposal, hash) in [(0, &proposal0, hash0), (1, &proposal1, hash1)] {
+        let rel_height = u64::from(round_id);
+        let msg = create_proposal_message(round_id, proposal, &validators, &alice_kp);
+        zug.handle_message(&mut rng, sender, msg, timestamp);
+        let msg = create_message(&validators, round_id, echo(hash), &bob_kp);
+        zug.handle_message(&mut rng, sender, msg, timestamp);
+        let msg = create_message(&validators, round_id, vote(true), &alice_kp);
+        zug.handle_message(&mut rng, sender, msg, timestamp);
+        let msg = create_message(&validators, round_id, vote(true), &bob_kp);
+        let outcomes = zug.handle_message(&mut rng, sender, msg, timestamp);
+        expect_finalized(&outcomes, &[(proposal, rel_height)]);
+    }
+
+    // The stale proposal gets a quorum of echoes, but it is not accepted.
+    let msg = create_message(&validators, 2, echo(stale_hash2), &bob_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    let msg = create_proposal_message(2, &stale_proposal2, &validators, &alice_kp);
+    let outcomes = zug.handle_message(&mut rng, sender, msg, timestamp);
+    assert!(!outcomes
+        .iter()
+        .any(|outcome| matches!(outcome, ProtocolOutcome::HandledProposedBlock(_))));
+    assert!(zug.round(2).and_then(Round::proposal).is_none());
+    assert!(!zug.has_accepted_proposal(2));
+}
+
+/// Tests that `known_block_values` lists the blocks from both stored proposals and proposals
+/// waiting for validation, without duplicates.
+#[test]
+fn zug_known_block_values() {
+    let mut rng = crate::new_rng();
+    let (weights, validators) = abc_weights(60, 30, 10);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+
+    // Alice is the leader in rounds 0, 1 and 2.
+    let mut zug = new_test_zug(weights, vec![], &[alice_idx, alice_idx, alice_idx]);
+
+    let alice_kp = Keypair::from(ALICE_SECRET_KEY.clone());
+
+    let sender = *ALICE_NODE_ID;
+    let timestamp = Timestamp::from(100000);
+
+    let block = new_payload(false);
+    // This block contains an accusation, so it needs validation.
+    let block_to_validate = Arc::new(BlockPayload::new(
+        vec![],
+        vec![],
+        vec![CAROL_PUBLIC_KEY.clone()],
+        false,
+    ));
+    let proposal = Proposal::<ClContext> {
+        timestamp,
+        maybe_block: Some(block.clone()),
+        maybe_parent_round_id: None,
+        inactive: None,
+    };
+    let proposal_to_validate = Proposal::<ClContext> {
+        timestamp,
+        maybe_block: Some(block_to_validate.clone()),
+        maybe_parent_round_id: None,
+        inactive: None,
+    };
+
+    assert!(zug.known_block_values().is_empty());
+
+    // The same proposal in rounds 0 and 1, and one that needs validation in round 2.
+    for round_id in 0..2 {
+        let msg = create_proposal_message(round_id, &proposal, &validators, &alice_kp);
+        zug.handle_message(&mut rng, sender, msg, timestamp);
+    }
+    let msg = create_proposal_message(2, &proposal_to_validate, &validators, &alice_kp);
+    let outcomes = zug.handle_message(&mut rng, sender, msg, timestamp);
+    assert!(outcomes
+        .iter()
+        .any(|outcome| matches!(outcome, ProtocolOutcome::ValidateConsensusValue { .. })));
+
+    let values = zug.known_block_values();
+    assert_eq!(2, values.len());
+    assert!(values.contains(&&block));
+    assert!(values.contains(&&block_to_validate));
+}
+
+/// Tests that a validator without weight is never selected as a leader and doesn't prevent rounds
+/// from getting committed.
+#[test]
+fn zug_zero_weight_validator() {
+    let mut rng = crate::new_rng();
+    let (weights, validators) = abc_weights(60, 40, 0);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+    let carol_idx = validators.get_index(&*CAROL_PUBLIC_KEY).unwrap();
+
+    let mut zug = new_test_zug(weights, vec![], &[alice_idx]);
+
+    for round_id in 0..1000 {
+        assert_ne!(carol_idx, zug.leader(round_id));
+    }
+
+    let alice_kp = Keypair::from(ALICE_SECRET_KEY.clone());
+    let bob_kp = Keypair::from(BOB_SECRET_KEY.clone());
+
+    let sender = *ALICE_NODE_ID;
+    let timestamp = Timestamp::from(100000);
+
+    let proposal0 = Proposal::<ClContext> {
+        timestamp,
+        maybe_block: Some(new_payload(false)),
+        maybe_parent_round_id: None,
+        inactive: None,
+    };
+    let hash0 = proposal0.hash();
+
+    // Carol never says anything, but Alice and Bob can finalize Alice's proposal.
+    let msg = create_proposal_message(0, &proposal0, &validators, &alice_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    let msg = create_message(&validators, 0, echo(hash0), &bob_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    let msg = create_message(&validators, 0, vote(true), &alice_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    let msg = create_message(&validators, 0, vote(true), &bob_kp);
+    let outcomes = zug.handle_message(&mut rng, sender, msg, timestamp);
+    expect_finalized(&outcomes, &[(&proposal0, 0)]);
+}
+
+/// Tests that disabling sync stops the sync requests to random peers, but not echoing and voting.
+#[test]
+fn zug_set_sync_enabled() {
+    let mut rng = crate::new_rng();
+    let (weights, validators) = abc_weights(60, 30, 10);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+
+    // The first round leader is Alice.
+    let mut zug = new_test_zug(weights, vec![], &[alice_idx]);
+
+    let alice_kp = Keypair::from(ALICE_SECRET_KEY.clone());
+    let bob_kp = Keypair::from(BOB_SECRET_KEY.clone());
+
+    let timeout = zug.config.sync_state_interval.expect("request state timer");
+    let sender = *ALICE_NODE_ID;
+    let mut timestamp = Timestamp::from(100000);
+
+    // We are Bob.
+    let dir = tempdir().unwrap();
+    zug.open_wal(dir.path().join("wal"), timestamp);
+    zug.activate_validator(BOB_PUBLIC_KEY.clone(), bob_kp, timestamp, None);
+
+    let proposal0 = Proposal::<ClContext> {
+        timestamp,
+        maybe_block: Some(new_payload(false)),
+        maybe_parent_round_id: None,
+        inactive: None,
+    };
+    let hash0 = proposal0.hash();
+
+    zug.set_sync_enabled(false);
+
+    // The timer is rescheduled, but no request is sent.
+    let mut outcomes = zug.handle_timer(timestamp, timestamp, TIMER_ID_SYNC_PEER, &mut rng);
+    expect_sync_timer(&outcomes, timestamp, timeout);
+    assert!(remove_requests_to_random(&mut outcomes).is_empty());
+
+    // We still echo Alice's proposal.
+    let msg = create_proposal_message(0, &proposal0, &validators, &alice_kp);
+    let mut outcomes = zug.handle_message(&mut rng, sender, msg, timestamp);
+    let gossip = remove_gossip(&validators, &mut outcomes);
+    assert!(gossip.iter().any(|msg| matches!(
+        msg,
+        Message::Signed(SignedMessage {
+            content: Content::Echo(hash),
+            ..
+        }) if *hash == hash0
+    )));
+
+    timestamp += timeout;
+    zug.set_sync_enabled(true);
+
+    // After enabling sync again, the next timer event sends a request.
+    let mut outcomes = zug.handle_timer(timestamp, timestamp, TIMER_ID_SYNC_PEER, &mut rng);
+    expect_sync_timer(&outcomes, timestamp, timeout);
+    assert_eq!(1, remove_requests_to_random(&mut outcomes).len());
+}
+
+/// Tests that `votes_needed` returns the missing `true` vote weight for an accepted proposal.
+#[test]
+fn zug_votes_needed() {
+    let mut rng = crate::new_rng();
+    let (weights, validators) = abc_weights(60, 30, 10);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+
+    // The first round leader is Alice.
+    let mut zug = new_test_zug(weights, vec![], &[alice_idx]);
+
+    let alice_kp = Keypair::from(ALICE_SECRET_KEY.clone());
+    let bob_kp = Keypair::from(BOB_SECRET_KEY.clone());
+    let carol_kp = Keypair::from(CAROL_SECRET_KEY.clone());
+
+    let sender = *ALICE_NODE_ID;
+    let timestamp = Timestamp::from(100000);
+
+    let proposal0 = Proposal::<ClContext> {
+        timestamp,
+        maybe_block: Some(new_payload(false)),
+        maybe_parent_round_id: None,
+        inactive: None,
+    };
+    let hash0 = proposal0.hash();
+
+    // There is no accepted proposal yet.
+    assert_eq!(None, zug.votes_needed(0));
+    let msg = create_proposal_message(0, &proposal0, &validators, &alice_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    assert_eq!(None, zug.votes_needed(0));
+
+    // The quorum threshold is 66, so we need more than that.
+    let msg = create_message(&validators, 0, echo(hash0), &bob_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    assert_eq!(Some(Weight(67)), zug.votes_needed(0));
+
+    let msg = create_message(&validators, 0, vote(true), &carol_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    assert_eq!(Some(Weight(57)), zug.votes_needed(0));
+
+    let msg = create_message(&validators, 0, vote(true), &bob_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    assert_eq!(Some(Weight(27)), zug.votes_needed(0));
+
+    // Once the round is committed, no more votes are needed.
+    let msg = create_message(&validators, 0, vote(true), &alice_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    assert_eq!(None, zug.votes_needed(0));
+}
+
+/// Tests that the validator count and total weight include all validators in the era.
+#[test]
+fn zug_validator_count_and_total_weight() {
+    let (weights, validators) = abc_weights(60, 30, 10);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+    let zug = new_test_zug(weights, vec![], &[alice_idx]);
+    assert_eq!(3, zug.validator_count());
+    assert_eq!(Weight(100), zug.total_weight());
+}
+
+/// Tests that two nodes with the same state create identical sync requests for the same window,
+/// even if there are competing echoes with equal weight.
+#[test]
+fn zug_sync_request_for_is_deterministic() {
+    let mut rng = crate::new_rng();
+    let (weights, validators) = abc_weights(50, 25, 25);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+
+    let mut zug1 = new_test_zug(weights.clone(), vec![], &[alice_idx]);
+    let mut zug2 = new_test_zug(weights, vec![], &[alice_idx]);
+
+    let bob_kp = Keypair::from(BOB_SECRET_KEY.clone());
+    let carol_kp = Keypair::from(CAROL_SECRET_KEY.clone());
+
+    let sender = *ALICE_NODE_ID;
+    let timestamp = Timestamp::from(100000);
+
+    let hashes = [false, true].map(|random_bit| {
+        Proposal::<ClContext> {
+            timestamp,
+            maybe_block: Some(new_payload(random_bit)),
+            maybe_parent_round_id: None,
+            inactive: None,
+        }
+        .hash()
+    });
+
+    // Bob and Carol echo different hashes with equal weight. The nodes receive them in different
+    // orders.
+    let bob_msg = create_message(&validators, 0, echo(hashes[0]), &bob_kp);
+    let carol_msg = create_message(&validators, 0, echo(hashes[1]), &carol_kp);
+    zug1.handle_message(&mut rng, sender, bob_msg.clone(), timestamp);
+    zug1.handle_message(&mut rng, sender, carol_msg.clone(), timestamp);
+    zug2.handle_message(&mut rng, sender, carol_msg, timestamp);
+    zug2.handle_message(&mut rng, sender, bob_msg, timestamp);
+
+    for first_validator_idx in (0..3).map(ValidatorIndex) {
+        let request1 = zug1.sync_request_for(0, first_validator_idx);
+        let request2 = zug2.sync_request_for(0, first_validator_idx);
+        assert_eq!(request1.as_raw(), request2.as_raw());
+        let sync_request = request1.deserialize_expect::<SyncRequest<ClContext>>();
+        assert_eq!(hashes.iter().max(), sync_request.proposal_hash.as_ref());
+    }
+}
+
+/// Tests that a message from the WAL with an out-of-range validator index is skipped instead of
+/// causing a panic, and that the entries after it are still replayed.
+#[test]
+fn zug_wal_with_invalid_validator_index() {
+    let (weights, validators) = abc_weights(60, 30, 10);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+    let bob_idx = validators.get_index(&*BOB_PUBLIC_KEY).unwrap();
+
+    let bob_kp = Keypair::from(BOB_SECRET_KEY.clone());
+
+    let timestamp = Timestamp::from(100000);
+    let hash = ClContext::hash(&[1]);
+
+    // A valid echo by Bob, followed by one claiming to be from a fourth validator, and another
+    // valid one.
+    let valid_msg = create_signed_message(&validators, 0, echo(hash), &bob_kp);
+    let mut invalid_msg = create_signed_message(&validators, 1, echo(hash), &bob_kp);
+    invalid_msg.validator_idx = ValidatorIndex(3);
+    let later_msg = create_signed_message(&validators, 2, echo(hash), &bob_kp);
+
+    let dir = tempdir().unwrap();
+    let wal_file = dir.path().join("wal");
+    let mut write_wal = WriteWal::<ClContext>::new(&wal_file).unwrap();
+    write_wal
+        .record_entry(&Entry::SignedMessage(valid_msg))
+        .unwrap();
+    write_wal
+        .record_entry(&Entry::SignedMessage(invalid_msg))
+        .unwrap();
+    write_wal
+        .record_entry(&Entry::SignedMessage(later_msg))
+        .unwrap();
+    drop(write_wal);
+
+    let mut zug = new_test_zug(weights, vec![], &[alice_idx]);
+    zug.open_wal(wal_file, timestamp);
+    assert!(zug.has_echoed(0, bob_idx));
+    assert!(zug.round(1).is_none());
+    assert!(zug.has_echoed(2, bob_idx));
+}
+
+/// Tests that `never_participated` reports silent validators only.
+#[test]
+fn zug_never_participated() {
+    let mut rng = crate::new_rng();
+    let (weights, validators) = abc_weights(60, 30, 10);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+    let bob_idx = validators.get_index(&*BOB_PUBLIC_KEY).unwrap();
+    let carol_idx = validators.get_index(&*CAROL_PUBLIC_KEY).unwrap();
+
+    // The first round leader is Alice.
+    let mut zug = new_test_zug(weights, vec![], &[alice_idx]);
+
+    let alice_kp = Keypair::from(ALICE_SECRET_KEY.clone());
+    let bob_kp = Keypair::from(BOB_SECRET_KEY.clone());
+
+    let sender = *ALICE_NODE_ID;
+    let timestamp = Timestamp::from(100000);
+
+    let proposal0 = Proposal::<ClContext> {
+        timestamp,
+        maybe_block: Some(new_payload(false)),
+        maybe_parent_round_id: None,
+        inactive: None,
+    };
+
+    let mut all_idxs = vec![alice_idx, bob_idx, carol_idx];
+    all_idxs.sort();
+    assert_eq!(all_idxs, zug.never_participated());
+
+    // Alice proposes and echoes, and Bob votes. Carol remains silent.
+    let msg = create_proposal_message(0, &proposal0, &validators, &alice_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    let msg = create_message(&validators, 0, vote(false), &bob_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+
+    assert_eq!(vec![carol_idx], zug.never_participated());
+}
+
+/// Tests that force-starting a future round makes it the current round and schedules its proposal
+/// timeout.
+#[test]
+fn zug_force_start_round() {
+    let (weights, validators) = abc_weights(60, 30, 10);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+    let mut zug = new_test_zug(weights, vec![], &[alice_idx]);
+
+    let timestamp = Timestamp::from(100000);
+    let outcomes = zug.force_start_round(5, timestamp);
+    expect_timer(
+        &outcomes,
+        timestamp + zug.proposal_timeout(),
+        TIMER_ID_UPDATE,
+    );
+    assert_eq!(5, zug.current_round);
+    assert!(zug.round(5).is_some());
+
+    // Earlier rounds can't be started again.
+    assert!(zug.force_start_round(3, timestamp).is_empty());
+    assert_eq!(5, zug.current_round);
+}
+
+/// Tests that `max_echo_weight` returns the weight of the largest set of echoes for one hash.
+#[test]
+fn zug_max_echo_weight() {
+    let mut rng = crate::new_rng();
+    let (weights, validators) = abc_weights(50, 30, 20);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+
+    let mut zug = new_test_zug(weights, vec![], &[alice_idx]);
+
+    let bob_kp = Keypair::from(BOB_SECRET_KEY.clone());
+    let carol_kp = Keypair::from(CAROL_SECRET_KEY.clone());
+
+    let sender = *ALICE_NODE_ID;
+    let timestamp = Timestamp::from(100000);
+    let hash_b = ClContext::hash(&[1]);
+    let hash_c = ClContext::hash(&[2]);
+
+    assert_eq!(None, zug.max_echo_weight(0));
+
+    // Bob and Carol echo different hashes.
+    let msg = create_message(&validators, 0, echo(hash_c), &carol_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    assert_eq!(Some(Weight(20)), zug.max_echo_weight(0));
+    let msg = create_message(&validators, 0, echo(hash_b), &bob_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    assert_eq!(Some(Weight(30)), zug.max_echo_weight(0));
+}
+
+/// Tests that with `defer_future_proposals` enabled, a proposal from a future round doesn't
+/// instantiate the round until it becomes current.
+#[test]
+fn zug_defer_future_proposals() {
+    let mut rng = crate::new_rng();
+    let (weights, validators) = abc_weights(60, 30, 10);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+
+    // Alice is the leader in rounds 0, 1 and 2.
+    let mut zug = new_test_zug(weights, vec![], &[alice_idx, alice_idx, alice_idx]);
+    zug.config.defer_future_proposals = true;
+
+    let alice_kp = Keypair::from(ALICE_SECRET_KEY.clone());
+    let bob_kp = Keypair::from(BOB_SECRET_KEY.clone());
+
+    let sender = *ALICE_NODE_ID;
+    let timestamp = Timestamp::from(100000);
+
+    let proposal2 = Proposal::<ClContext> {
+        timestamp,
+        maybe_block: Some(new_payload(false)),
+        maybe_parent_round_id: None,
+        inactive: None,
+    };
+
+    let msg = create_proposal_message(2, &proposal2, &validators, &alice_kp);
+    assert!(zug
+        .handle_message(&mut rng, sender, msg, timestamp)
+        .is_empty());
+    assert!(zug.round(2).is_none());
+
+    // Rounds 0 and 1 are skipped. Only then the proposal is handled.
+    for round_id in 0..2 {
+        assert!(zug.round(2).is_none());
+        let msg = create_message(&validators, round_id, vote(false), &alice_kp);
+        zug.handle_message(&mut rng, sender, msg, timestamp);
+        let msg = create_message(&validators, round_id, vote(false), &bob_kp);
+        zug.handle_message(&mut rng, sender, msg, timestamp);
+    }
+    assert_eq!(2, zug.current_round);
+    assert_eq!(
+        Some(&proposal2),
+        zug.round(2)
+            .and_then(Round::proposal)
+            .map(HashedProposal::inner)
+    );
+}
+
+/// Tests that only proposals with a valid echo by the round's leader are deferred, and that a
+/// deferred proposal can't be replaced by a later one.
+#[test]
+fn zug_defer_future_proposals_checks_echo() {
+    let mut rng = crate::new_rng();
+    let (weights, validators) = abc_weights(60, 30, 10);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+
+    // Alice is the leader in rounds 0, 1 and 2.
+    let mut zug = new_test_zug(weights, vec![], &[alice_idx, alice_idx, alice_idx]);
+    zug.config.defer_future_proposals = true;
+
+    let alice_kp = Keypair::from(ALICE_SECRET_KEY.clone());
+    let bob_kp = Keypair::from(BOB_SECRET_KEY.clone());
+
+    let sender = *BOB_NODE_ID;
+    let timestamp = Timestamp::from(100000);
+
+    let proposal2 = Proposal::<ClContext> {
+        timestamp,
+        maybe_block: Some(new_payload(false)),
+        maybe_parent_round_id: None,
+        inactive: None,
+    };
+    let forged_proposal2 = Proposal::<ClContext> {
+        timestamp,
+        maybe_block: Some(new_payload(true)),
+        maybe_parent_round_id: None,
+        inactive: None,
+    };
+
+    // A proposal whose echo claims to be from Alice but has Bob's signature is not deferred.
+    let mut forged_echo =
+        create_signed_message(&validators, 2, echo(forged_proposal2.hash()), &alice_kp);
+    forged_echo.signature =
+        create_signed_message(&validators, 2, echo(forged_proposal2.hash()), &bob_kp).signature;
+    let msg = SerializedMessage::from_message(&Message::Proposal {
+        round_id: 2,
+        instance_id: *zug.instance_id(),
+        proposal: forged_proposal2.clone(),
+        echo: forged_echo,
+    });
+    let outcomes = zug.handle_message(&mut rng, sender, msg, timestamp);
+    assert!(outcomes.contains(&ProtocolOutcome::Disconnect(sender)));
+    assert_eq!(0, zug.deferred_proposal_count);
+
+    // Alice's real proposal is deferred, and a second one from her doesn't replace it.
+    let msg = create_proposal_message(2, &proposal2, &validators, &alice_kp);
+    assert!(zug
+        .handle_message(&mut rng, *ALICE_NODE_ID, msg, timestamp)
+        .is_empty());
+    let msg = create_proposal_message(2, &forged_proposal2, &validators, &alice_kp);
+    assert!(zug
+        .handle_message(&mut rng, sender, msg, timestamp)
+        .is_empty());
+    assert_eq!(1, zug.deferred_proposal_count);
+
+    // Rounds 0 and 1 are skipped, and the original proposal is handled.
+    for round_id in 0..2 {
+        let msg = create_message(&validators, round_id, vote(false), &alice_kp);
+        zug.handle_message(&mut rng, sender, msg, timestamp);
+        let msg = create_message(&validators, round_id, vote(false), &bob_kp);
+        zug.handle_message(&mut rng, sender, msg, timestamp);
+    }
+    assert_eq!(2, zug.current_round);
+    assert_eq!(0, zug.deferred_proposal_count);
+    assert_eq!(
+        Some(&proposal2),
+        zug.round(2)
+            .and_then(Round::proposal)
+            .map(HashedProposal::inner)
+    );
+}
+
+/// Tests that the missing proposals timer detects rounds that have quorums of echoes and votes but
+/// no proposal, and requests them from a random peer unless syncing is disabled.
+#[test]
+fn zug_request_missing_proposal() {
+    let mut rng = crate::new_rng();
+    let (weights, validators) = abc_weights(60, 30, 10);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+
+    let mut zug = new_test_zug(weights, vec![], &[alice_idx]);
+
+    let alice_kp = Keypair::from(ALICE_SECRET_KEY.clone());
+    let bob_kp = Keypair::from(BOB_SECRET_KEY.clone());
+
+    let sender = *ALICE_NODE_ID;
+    let timestamp = Timestamp::from(100000);
+
+    let proposal0 = Proposal::<ClContext> {
+        timestamp,
+        maybe_block: Some(new_payload(false)),
+        maybe_parent_round_id: None,
+        inactive: None,
+    };
+    let hash0 = proposal0.hash();
+
+    // Without any quorum, nothing is requested.
+    let mut outcomes = zug.handle_timer(timestamp, timestamp, TIMER_ID_MISSING_PROPOSALS, &mut rng);
+    assert!(remove_requests_to_random(&mut outcomes).is_empty());
+
+    // Alice and Bob echo and vote for the proposal, but we never receive it.
+    for kp in [&alice_kp, &bob_kp] {
+        let msg = create_message(&validators, 0, echo(hash0), kp);
+        zug.handle_message(&mut rng, sender, msg, timestamp);
+        let msg = create_message(&validators, 0, vote(true), kp);
+        zug.handle_message(&mut rng, sender, msg, timestamp);
+    }
+    assert_eq!(Some(hash0), zug.round(0).and_then(Round::quorum_echoes));
+    assert!(zug.round(0).and_then(Round::proposal).is_none());
+
+    // The timer is scheduled with the sync state interval once the era is current.
+    let interval = zug.config.sync_state_interval.expect("sync state interval");
+    let outcomes = zug.handle_is_current(timestamp);
+    expect_timer(&outcomes, timestamp + interval, TIMER_ID_MISSING_PROPOSALS);
+
+    // While syncing is disabled, nothing is requested, but the timer is rescheduled.
+    zug.set_sync_enabled(false);
+    let mut outcomes = zug.handle_timer(timestamp, timestamp, TIMER_ID_MISSING_PROPOSALS, &mut rng);
+    assert!(remove_requests_to_random(&mut outcomes).is_empty());
+    expect_timer(&outcomes, timestamp + interval, TIMER_ID_MISSING_PROPOSALS);
+
+    // The participation timer doesn't request anything.
+    let mut outcomes = zug.handle_timer(timestamp, timestamp, TIMER_ID_LOG_PARTICIPATION, &mut rng);
+    assert!(remove_requests_to_random(&mut outcomes).is_empty());
+
+    zug.set_sync_enabled(true);
+    let mut outcomes = zug.handle_timer(timestamp, timestamp, TIMER_ID_MISSING_PROPOSALS, &mut rng);
+    expect_timer(&outcomes, timestamp + interval, TIMER_ID_MISSING_PROPOSALS);
+    let requests = remove_requests_to_random(&mut outcomes);
+    assert_eq!(1, requests.len());
+    assert_eq!(0, requests[0].round_id);
+    assert_eq!(Some(hash0), requests[0].proposal_hash);
+    assert!(!requests[0].has_proposal);
+
+    // Once we have the proposal, it isn't requested anymore.
+    let msg = create_proposal_message(0, &proposal0, &validators, &alice_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    let mut outcomes = zug.handle_timer(timestamp, timestamp, TIMER_ID_MISSING_PROPOSALS, &mut rng);
+    assert!(remove_requests_to_random(&mut outcomes).is_empty());
+}
+
+/// Tests that the FTT stays fixed while the faulty weight grows with banned and faulty validators.
+#[test]
+fn zug_fault_tolerance_threshold_weight() {
+    let mut rng = crate::new_rng();
+    let (weights, validators) = abc_weights(60, 30, 10);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+
+    let chainspec = new_test_chainspec(weights.clone());
+    let expected_ftt = common::ftt::<ClContext>(
+        chainspec.core_config.finality_threshold_fraction,
+        &validators,
+    );
+
+    // Carol is banned from the start.
+    let mut zug = new_test_zug(weights, vec![CAROL_PUBLIC_KEY.clone()], &[alice_idx]);
+    assert_eq!(expected_ftt, zug.fault_tolerance_threshold_weight());
+    assert_eq!(Weight(10), zug.current_faulty_weight());
+
+    let bob_kp = Keypair::from(BOB_SECRET_KEY.clone());
+    let sender = *ALICE_NODE_ID;
+    let timestamp = Timestamp::from(100000);
+
+    // Bob echoes two different proposals and becomes faulty.
+    let hash0 = ClContext::hash(&[0]);
+    let hash1 = ClContext::hash(&[1]);
+    let msg = create_message(&validators, 0, echo(hash0), &bob_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    assert_eq!(Weight(10), zug.current_faulty_weight());
+    let msg = create_message(&validators, 0, echo(hash1), &bob_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    assert_eq!(Weight(40), zug.current_faulty_weight());
+    assert_eq!(expected_ftt, zug.fault_tolerance_threshold_weight());
+}
+
+/// Tests that `quorum_changes_from_fault` predicts the rounds a validator's fault would decide.
+#[test]
+fn zug_quorum_changes_from_fault() {
+    let mut rng = crate::new_rng();
+    let (weights, validators) = abc_weights(60, 30, 10);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+    let bob_idx = validators.get_index(&*BOB_PUBLIC_KEY).unwrap();
+    let carol_idx = validators.get_index(&*CAROL_PUBLIC_KEY).unwrap();
+
+    let mut zug = new_test_zug(weights, vec![], &[alice_idx]);
+
+    let alice_kp = Keypair::from(ALICE_SECRET_KEY.clone());
+    let bob_kp = Keypair::from(BOB_SECRET_KEY.clone());
+    let carol_kp = Keypair::from(CAROL_SECRET_KEY.clone());
+    let sender = *ALICE_NODE_ID;
+    let timestamp = Timestamp::from(100000);
+
+    // Bob echoes a proposal in round 0, and Alice votes `false` in round 1.
+    let hash0 = ClContext::hash(&[0]);
+    let msg = create_message(&validators, 0, echo(hash0), &bob_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    let msg = create_message(&validators, 1, vote(false), &alice_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+
+    // With Alice faulty, Bob's echo would make a quorum. With Bob or Carol faulty, Alice's vote
+    // would.
+    assert_eq!(vec![0], zug.quorum_changes_from_fault(alice_idx));
+    assert_eq!(vec![1], zug.quorum_changes_from_fault(bob_idx));
+    assert_eq!(vec![1], zug.quorum_changes_from_fault(carol_idx));
+    assert!(zug.round(1).and_then(Round::quorum_votes).is_none());
+
+    // Carol double-signs in round 2, and the prediction comes true.
+    let hash1 = ClContext::hash(&[1]);
+    let msg = create_message(&validators, 2, echo(hash0), &carol_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    let msg = create_message(&validators, 2, echo(hash1), &carol_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    assert_eq!(Some(false), zug.round(1).and_then(Round::quorum_votes));
+    assert!(zug.round(0).and_then(Round::quorum_echoes).is_none());
+    assert!(zug.quorum_changes_from_fault(carol_idx).is_empty());
+}
+
+/// Tests that after a fault only rounds within the `fault_rescan_window` are checked for new
+/// quorums right away, and later ones once they become current.
+#[test]
+fn zug_fault_rescan_window() {
+    let mut rng = crate::new_rng();
+    let (weights, validators) = abc_weights(60, 30, 10);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+
+    let mut zug = new_test_zug(weights, vec![], &[alice_idx]);
+    zug.config.fault_rescan_window = 2;
+
+    let alice_kp = Keypair::from(ALICE_SECRET_KEY.clone());
+    let carol_kp = Keypair::from(CAROL_SECRET_KEY.clone());
+    let sender = *ALICE_NODE_ID;
+    let timestamp = Timestamp::from(100000);
+
+    // Alice votes to skip rounds 1 to 10. That's not a quorum on its own.
+    for round_id in 1..=10 {
+        let msg = create_message(&validators, round_id, vote(false), &alice_kp);
+        zug.handle_message(&mut rng, sender, msg, timestamp);
+    }
+
+    // Carol double-signs. Together with Alice she has a quorum, but only rounds up to 2 are
+    // checked.
+    let msg = create_message(&validators, 0, echo(ClContext::hash(&[0])), &carol_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    let msg = create_message(&validators, 0, echo(ClContext::hash(&[1])), &carol_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    assert_eq!(Some(false), zug.round(2).and_then(Round::quorum_votes));
+    assert!(zug.round(3).and_then(Round::quorum_votes).is_none());
+    assert_eq!(0, zug.current_round);
+
+    // Once round 0 is skippable, the later rounds are checked as they become current.
+    let msg = create_message(&validators, 0, vote(false), &alice_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    assert_eq!(Some(false), zug.round(10).and_then(Round::quorum_votes));
+    assert_eq!(11, zug.current_round);
+}
+
+/// Tests that rounds waiting to be rescanned after a fault are still checked if a later round is
+/// force-started.
+#[test]
+fn zug_force_start_round_after_fault_rescan() {
+    let mut rng = crate::new_rng();
+    let (weights, validators) = abc_weights(60, 30, 10);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+
+    let mut zug = new_test_zug(weights, vec![], &[alice_idx]);
+    zug.config.fault_rescan_window = 2;
+
+    let alice_kp = Keypair::from(ALICE_SECRET_KEY.clone());
+    let carol_kp = Keypair::from(CAROL_SECRET_KEY.clone());
+    let sender = *ALICE_NODE_ID;
+    let timestamp = Timestamp::from(100000);
+
+    // Alice votes to skip rounds 1 to 10, and Carol double-signs: Rounds 3 to 10 are not checked
+    // for new quorums yet.
+    for round_id in 1..=10 {
+        let msg = create_message(&validators, round_id, vote(false), &alice_kp);
+        zug.handle_message(&mut rng, sender, msg, timestamp);
+    }
+    let msg = create_message(&validators, 0, echo(ClContext::hash(&[0])), &carol_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    let msg = create_message(&validators, 0, echo(ClContext::hash(&[1])), &carol_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    assert!(zug.round(3).and_then(Round::quorum_votes).is_none());
+
+    // We jump past all of them. The next update still finds their quorums.
+    zug.force_start_round(12, timestamp);
+    zug.handle_timer(timestamp, timestamp, TIMER_ID_UPDATE, &mut rng);
+    for round_id in 3..=10 {
+        assert_eq!(
+            Some(false),
+            zug.round(round_id).and_then(Round::quorum_votes)
+        );
+    }
+    assert_eq!(12, zug.current_round);
+}
+
+/// Tests that finality certificates are verified, and rejected if signatures are wrong or missing.
+#[test]
+fn zug_verify_finality_certificate() {
+    let mut rng = crate::new_rng();
+    let (weights, validators) = abc_weights(60, 30, 10);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+    let bob_idx = validators.get_index(&*BOB_PUBLIC_KEY).unwrap();
+
+    let mut zug = new_test_zug(weights, vec![], &[alice_idx]);
+
+    let alice_kp = Keypair::from(ALICE_SECRET_KEY.clone());
+    let bob_kp = Keypair::from(BOB_SECRET_KEY.clone());
+    let sender = *ALICE_NODE_ID;
+    let timestamp = Timestamp::from(100000);
+
+    // Alice and Bob echo and vote for a proposal in round 0.
+    let hash0 = ClContext::hash(&[0]);
+    assert!(zug.finality_certificate(0).is_none());
+    for kp in [&alice_kp, &bob_kp] {
+        let msg = create_message(&validators, 0, echo(hash0), kp);
+        zug.handle_message(&mut rng, sender, msg, timestamp);
+        let msg = create_message(&validators, 0, vote(true), kp);
+        zug.handle_message(&mut rng, sender, msg, timestamp);
+    }
+
+    let cert = zug.finality_certificate(0).expect("expected certificate");
+    assert_eq!(hash0, cert.proposal_hash);
+    assert!(zug.verify_finality_certificate(&cert));
+
+    // A node for the same era with a fresh state accepts the certificate, too.
+    let (weights, _) = abc_weights(60, 30, 10);
+    let other_zug = new_test_zug(weights, vec![], &[alice_idx]);
+    assert!(other_zug.verify_finality_certificate(&cert));
+
+    // Bob's echo signature is not a valid `true` vote signature.
+    let mut forged_cert = cert.clone();
+    let bob_echo_signature = cert.echoes[&bob_idx];
+    forged_cert.true_votes.insert(bob_idx, bob_echo_signature);
+    assert!(!zug.verify_finality_certificate(&forged_cert));
+
+    // Bob's vote alone is not a quorum.
+    let mut insufficient_cert = cert.clone();
+    insufficient_cert.true_votes.remove(&alice_idx);
+    assert!(!zug.verify_finality_certificate(&insufficient_cert));
+
+    // Certificates from other protocol instances are rejected.
+    let mut other_instance_cert = cert;
+    other_instance_cert.instance_id = ClContext::hash(&[1]);
+    assert!(!zug.verify_finality_certificate(&other_instance_cert));
+}
+
+/// Tests that a peer with the proposal but without a quorum of `true` votes gets a finality
+/// certificate in response to their sync request, and can finalize the round with it.
+#[test]
+fn zug_sync_request_answered_with_finality_certificate() {
+    let mut rng = crate::new_rng();
+    let (weights, validators) = abc_weights(60, 30, 10);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+    let bob_idx = validators.get_index(&*BOB_PUBLIC_KEY).unwrap();
+
+    let mut zug = new_test_zug(weights, vec![], &[alice_idx]);
+
+    let alice_kp = Keypair::from(ALICE_SECRET_KEY.clone());
+    let bob_kp = Keypair::from(BOB_SECRET_KEY.clone());
+    let sender = *ALICE_NODE_ID;
+    let timestamp = Timestamp::from(100000);
+
+    // Alice and Bob echo and vote for a proposal in round 0.
+    let hash0 = ClContext::hash(&[0]);
+    for kp in [&alice_kp, &bob_kp] {
+        let msg = create_message(&validators, 0, echo(hash0), kp);
+        zug.handle_message(&mut rng, sender, msg, timestamp);
+        let msg = create_message(&validators, 0, vote(true), kp);
+        zug.handle_message(&mut rng, sender, msg, timestamp);
+    }
+
+    // The requester has the proposal, but no votes.
+    let msg = SyncRequest::<ClContext> {
+        round_id: 0,
+        proposal_hash: Some(hash0),
+        has_proposal: true,
+        first_validator_idx: alice_idx,
+        echoes: 0,
+        true_votes: 0,
+        false_votes: 0,
+        active: 0,
+        faulty: 0,
+        instance_id: *zug.instance_id(),
+    };
+    let (_, response) = zug.handle_request_message(
+        &mut rng,
+        sender,
+        SerializedMessage::from_message(&msg),
+        timestamp,
+    );
+    let response = response.expect("response");
+    match response.deserialize_expect() {
+        Message::FinalityCertificate(cert) => assert_eq!(zug.finality_certificate(0), Some(cert)),
+        result => panic!("unexpected message: {:?}", result),
+    }
+
+    // A node for the same era with a fresh state gets the quorums from the certificate.
+    let (weights, _) = abc_weights(60, 30, 10);
+    let mut other_zug = new_test_zug(weights, vec![], &[alice_idx]);
+    other_zug.handle_message(&mut rng, sender, response, timestamp);
+    let round = other_zug.round(0).expect("round 0");
+    assert_eq!(Some(hash0), round.quorum_echoes());
+    assert_eq!(Some(true), round.quorum_votes());
+
+    // If the requester already has a quorum of votes, they get a regular sync response.
+    let msg = SyncRequest {
+        true_votes: zug.validator_bit_field(alice_idx, vec![alice_idx, bob_idx].into_iter()),
+        ..msg
+    };
+    let (_, response) = zug.handle_request_message(
+        &mut rng,
+        sender,
+        SerializedMessage::from_message(&msg),
+        timestamp,
+    );
+    match response.expect("response").deserialize_expect() {
+        Message::SyncResponse(_) => {}
+        result => panic!("unexpected message: {:?}", result),
+    }
+
+    // An invalid certificate gets the sender disconnected.
+    let mut insufficient_cert = zug.finality_certificate(0).expect("expected certificate");
+    insufficient_cert.true_votes.remove(&alice_idx);
+    let msg = SerializedMessage::from_message(&Message::FinalityCertificate(insufficient_cert));
+    let outcomes = other_zug.handle_message(&mut rng, sender, msg, timestamp);
+    assert!(outcomes.contains(&ProtocolOutcome::Disconnect(sender)));
+}
+
+/// Tests that the time from receiving to finalizing a proposal is recorded for its round.
+#[test]
+fn zug_finalization_latencies() {
+    let mut rng = crate::new_rng();
+    let (weights, validators) = abc_weights(60, 30, 10);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+
+    let mut zug = new_test_zug(weights, vec![], &[alice_idx]);
+
+    let alice_kp = Keypair::from(ALICE_SECRET_KEY.clone());
+    let bob_kp = Keypair::from(BOB_SECRET_KEY.clone());
+    let sender = *ALICE_NODE_ID;
+    let timestamp = Timestamp::from(100000);
+
+    let proposal0 = Proposal::<ClContext> {
+        timestamp,
+        maybe_block: Some(new_payload(false)),
+        maybe_parent_round_id: None,
+        inactive: None,
+    };
+    let hash0 = proposal0.hash();
+
+    // We receive the proposal and Bob's echo at 100000.
+    let msg = create_proposal_message(0, &proposal0, &validators, &alice_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    let msg = create_message(&validators, 0, echo(hash0), &bob_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    assert!(zug.finalization_latencies().is_empty());
+
+    // The votes arrive 500 ms later, and the proposal gets finalized.
+    let later = timestamp + TimeDiff::from_millis(500);
+    let msg = create_message(&validators, 0, vote(true), &alice_kp);
+    zug.handle_message(&mut rng, sender, msg, later);
+    let msg = create_message(&validators, 0, vote(true), &bob_kp);
+    let outcomes = zug.handle_message(&mut rng, sender, msg, later);
+    expect_finalized(&outcomes, &[(&proposal0, 0)]);
+    assert_eq!(
+        vec![(0, TimeDiff::from_millis(500))],
+        zug.finalization_latencies()
+    );
+}
+
+/// Tests that banned validators and unknown indices can never be the leader.
+#[test]
+fn zug_can_propose() {
+    let (weights, validators) = abc_weights(60, 30, 10);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+    let bob_idx = validators.get_index(&*BOB_PUBLIC_KEY).unwrap();
+    let carol_idx = validators.get_index(&*CAROL_PUBLIC_KEY).unwrap();
+
+    // Carol is banned, so she is excluded from the leader sequence.
+    let zug = new_test_zug(weights, vec![CAROL_PUBLIC_KEY.clone()], &[alice_idx]);
+    assert!(zug.can_propose(alice_idx));
+    assert!(zug.can_propose(bob_idx));
+    assert!(!zug.can_propose(carol_idx));
+    assert!(!zug.can_propose(ValidatorIndex(3)));
+}
+
+/// Tests that computing the participation status of all validators at once gives the expected
+/// status for each of them.
+#[test]
+fn zug_participation_status_for_all() {
+    let mut rng = crate::new_rng();
+    let (weights, validators) = abc_weights(60, 30, 10);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+    let bob_idx = validators.get_index(&*BOB_PUBLIC_KEY).unwrap();
+    let carol_idx = validators.get_index(&*CAROL_PUBLIC_KEY).unwrap();
+
+    let mut zug = new_test_zug(weights, vec![], &[alice_idx]);
+
+    let alice_kp = Keypair::from(ALICE_SECRET_KEY.clone());
+    let bob_kp = Keypair::from(BOB_SECRET_KEY.clone());
+    let carol_kp = Keypair::from(CAROL_SECRET_KEY.clone());
+    let sender = *ALICE_NODE_ID;
+    let timestamp = Timestamp::from(100000);
+
+    let expect_statuses = |zug: &Zug<ClContext>, alice, bob, carol| {
+        let mut expected: ValidatorMap<Option<ParticipationStatus>> = vec![None; 3].into();
+        expected[alice_idx] = alice;
+        expected[bob_idx] = bob;
+        expected[carol_idx] = carol;
+        assert_eq!(expected, ParticipationStatus::for_all(zug));
+    };
+
+    // Nobody has participated yet.
+    let inactive = Some(ParticipationStatus::Inactive);
+    expect_statuses(&zug, inactive, inactive, inactive);
+
+    // Alice and Bob skip rounds 0 and 1, then Alice and Carol skip rounds 2 to 5. Bob was last
+    // seen more than two rounds ago.
+    for round_id in 0..6 {
+        let other_kp = if round_id < 2 { &bob_kp } else { &carol_kp };
+        for kp in [&alice_kp, other_kp] {
+            let msg = create_message(&validators, round_id, vote(false), kp);
+            zug.handle_message(&mut rng, sender, msg, timestamp);
+        }
+    }
+    assert_eq!(6, zug.current_round);
+    let bob_last_seen = Some(ParticipationStatus::LastSeenInRound(1));
+    expect_statuses(&zug, None, bob_last_seen, None);
+
+    // Carol double-signs.
+    let msg = create_message(&validators, 6, echo(ClContext::hash(&[0])), &carol_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    let msg = create_message(&validators, 6, echo(ClContext::hash(&[1])), &carol_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    let equivocated = Some(ParticipationStatus::Equivocated);
+    expect_statuses(&zug, None, bob_last_seen, equivocated);
+}
+
+/// Tests that the proposal hash doesn't depend on the order in which the block's approvals were
+/// added, and is the same after a serialization round trip.
+#[test]
+fn zug_proposal_hash_is_deterministic() {
+    let mut rng = crate::new_rng();
+    let deploy_hash = DeployHash::random(&mut rng);
+    let approvals: Vec<_> = [&*ALICE_SECRET_KEY, &*BOB_SECRET_KEY, &*CAROL_SECRET_KEY]
+        .into_iter()
+        .map(|secret_key| Approval::create(&deploy_hash, secret_key))
+        .collect();
+    let proposal_with_approvals = |approvals: Vec<Approval>| {
+        let deploy = DeployHashWithApprovals::new(deploy_hash, approvals.into_iter().collect());
+        let payload = BlockPayload::new(vec![deploy], vec![], vec![], false);
+        Proposal::<ClContext> {
+            timestamp: Timestamp::from(100000),
+            maybe_block: Some(Arc::new(payload)),
+            maybe_parent_round_id: None,
+            inactive: None,
+        }
+    };
+
+    let proposal = proposal_with_approvals(approvals.clone());
+    let reversed_proposal = proposal_with_approvals(approvals.into_iter().rev().collect());
+    assert_eq!(proposal.hash(), reversed_proposal.hash());
+    assert_eq!(
+        proposal.hash(),
+        *HashedProposal::new(reversed_proposal).hash()
+    );
+
+    let serialized = bincode::serialize(&proposal).expect("failed to serialize proposal");
+    let deserialized: Proposal<ClContext> =
+        bincode::deserialize(&serialized).expect("failed to deserialize proposal");
+    assert_eq!(proposal.hash(), deserialized.hash());
+}
+
+/// Tests that `protocol_stats` counts the messages we create, by kind of message and of outcome.
+#[test]
+fn zug_protocol_stats() {
+    let mut rng = crate::new_rng();
+    let (weights, validators) = abc_weights(60, 30, 10);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+    let bob_idx = validators.get_index(&*BOB_PUBLIC_KEY).unwrap();
+
+    // The first round leaders are Alice and Bob.
+    let mut zug = new_test_zug(weights, vec![], &[alice_idx, bob_idx]);
+
+    let alice_kp = Keypair::from(ALICE_SECRET_KEY.clone());
+    let bob_kp = Keypair::from(BOB_SECRET_KEY.clone());
+    let carol_kp = Keypair::from(CAROL_SECRET_KEY.clone());
+    let sender = *ALICE_NODE_ID;
+    let mut timestamp = Timestamp::from(100000);
+
+    // We are Bob.
+    let dir = tempdir().unwrap();
+    zug.open_wal(dir.path().join("wal"), timestamp);
+    zug.activate_validator(BOB_PUBLIC_KEY.clone(), bob_kp, timestamp, None);
+    let expected_stats = ProtocolStats {
+        retained_rounds: zug.rounds.len() as u64,
+        ..ProtocolStats::default()
+    };
+    assert_eq!(expected_stats, zug.protocol_stats());
+
+    let proposal0 = Proposal::<ClContext> {
+        timestamp,
+        maybe_block: Some(new_payload(false)),
+        maybe_parent_round_id: None,
+        inactive: None,
+    };
+
+    // Alice's proposal and our echo form a quorum, so we echo and vote `true`.
+    let msg = create_proposal_message(0, &proposal0, &validators, &alice_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    let stats = zug.protocol_stats();
+    assert_eq!(
+        (1, 1, 2),
+        (stats.echoes, stats.votes, stats.gossip_messages)
+    );
+
+    // Carol equivocates, and we gossip the evidence.
+    let echo0 = create_message(&validators, 0, echo(ClContext::hash(&[0])), &carol_kp);
+    let echo1 = create_message(&validators, 0, echo(ClContext::hash(&[1])), &carol_kp);
+    zug.handle_message(&mut rng, sender, echo0, timestamp);
+    zug.handle_message(&mut rng, sender, echo1, timestamp);
+    let stats = zug.protocol_stats();
+    assert_eq!((1, 3), (stats.evidence, stats.gossip_messages));
+
+    // Alice votes `true`, too, so round 0 is finalized. We are the leader in round 1, so we
+    // create a new block and propose it. The echo included in the proposal is not counted.
+    let msg = create_message(&validators, 0, vote(true), &alice_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    timestamp += zug.params.min_block_time();
+    let mut outcomes = zug.handle_timer(timestamp, timestamp, TIMER_ID_UPDATE, &mut rng);
+    let block_context = remove_create_new_block(&mut outcomes);
+    let proposed_block = ProposedBlock::new(new_payload(false), block_context);
+    zug.propose(proposed_block, timestamp);
+    let stats = zug.protocol_stats();
+    assert_eq!(
+        (1, 1, 4),
+        (stats.proposals, stats.echoes, stats.gossip_messages)
+    );
+
+    // The sync timer sends a request to a random peer.
+    let mut outcomes = zug.handle_timer(timestamp, timestamp, TIMER_ID_SYNC_PEER, &mut rng);
+    let requests = remove_requests_to_random(&mut outcomes).len() as u64;
+    assert_ne!(0, requests);
+    assert_eq!(requests, zug.protocol_stats().sync_requests);
+
+    // A peer that knows nothing about round 0 gets a sync response.
+    let msg = SyncRequest::<ClContext>::new_empty_round(0, alice_idx, 0, 0, *zug.instance_id());
+    let (_, response) = zug.handle_request_message(
+        &mut rng,
+        sender,
+        SerializedMessage::from_message(&msg),
+        timestamp,
+    );
+    assert!(response.is_some());
+
+    let expected_stats = ProtocolStats {
+        proposals: 1,
+        echoes: 1,
+        votes: 1,
+        evidence: 1,
+        sync_requests: requests,
+        sync_responses: 1,
+        gossip_messages: 4,
+        retained_rounds: zug.rounds.len() as u64,
+        ..ProtocolStats::default()
+    };
+    assert_eq!(expected_stats, zug.protocol_stats());
+}
+
+/// Tests that with a `vote_delay`, the `true` vote for an accepted proposal is only sent once the
+/// timer fires.
+#[test]
+fn zug_vote_delay() {
+    let mut rng = crate::new_rng();
+    let (weights, validators) = abc_weights(60, 30, 10);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+
+    // The first round leader is Alice.
+    let mut zug = new_test_zug(weights, vec![], &[alice_idx]);
+    let delay: TimeDiff = "300ms".parse().unwrap();
+    zug.config.vote_delay = Some(delay);
+
+    let alice_kp = Keypair::from(ALICE_SECRET_KEY.clone());
+    let bob_kp = Keypair::from(BOB_SECRET_KEY.clone());
+    let sender = *ALICE_NODE_ID;
+    let timestamp = Timestamp::from(100000);
+
+    // We are Bob.
+    let dir = tempdir().unwrap();
+    zug.open_wal(dir.path().join("wal"), timestamp);
+    zug.activate_validator(BOB_PUBLIC_KEY.clone(), bob_kp, timestamp, None);
+
+    let proposal0 = Proposal::<ClContext> {
+        timestamp,
+        maybe_block: Some(new_payload(false)),
+        maybe_parent_round_id: None,
+        inactive: None,
+    };
+    let hash0 = proposal0.hash();
+
+    let is_true_vote = |msg: &Message<ClContext>| {
+        matches!(
+            msg,
+            Message::Signed(SignedMessage {
+                content: Content::Vote(true),
+                ..
+            })
+        )
+    };
+
+    // Alice's proposal and our echo form a quorum, so the proposal is accepted. We echo, but
+    // don't vote yet.
+    let msg = create_proposal_message(0, &proposal0, &validators, &alice_kp);
+    let mut outcomes = zug.handle_message(&mut rng, sender, msg, timestamp);
+    expect_timer(&outcomes, timestamp + delay, TIMER_ID_VOTE);
+    let gossip = remove_gossip(&validators, &mut outcomes);
+    assert!(gossip.iter().any(|msg| matches!(
+        msg,
+        Message::Signed(SignedMessage {
+            content: Content::Echo(hash),
+            ..
+        }) if *hash == hash0
+    )));
+    assert!(!gossip.iter().any(is_true_vote));
+    assert!(zug.has_accepted_proposal(0));
+
+    // When the timer fires, we vote.
+    let mut outcomes = zug.handle_timer(
+        timestamp + delay,
+        timestamp + delay,
+        TIMER_ID_VOTE,
+        &mut rng,
+    );
+    let gossip = remove_gossip(&validators, &mut outcomes);
+    assert_eq!(1, gossip.iter().filter(|msg| is_true_vote(msg)).count());
+
+    // Firing it again doesn't create another vote.
+    let mut outcomes = zug.handle_timer(
+        timestamp + delay,
+        timestamp + delay,
+        TIMER_ID_VOTE,
+        &mut rng,
+    );
+    assert!(remove_gossip(&validators, &mut outcomes).is_empty());
+}
+
+/// Tests that `height_to_round` maps contiguous heights to the rounds with accepted proposals,
+/// leaving out skipped rounds.
+#[test]
+fn zug_height_to_round() {
+    let mut rng = crate::new_rng();
+    let (weights, validators) = abc_weights(60, 30, 10);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+
+    // Alice is the leader in rounds 0 to 3.
+    let mut zug = new_test_zug(weights, vec![], &[alice_idx; 4]);
+
+    let alice_kp = Keypair::from(ALICE_SECRET_KEY.clone());
+    let bob_kp = Keypair::from(BOB_SECRET_KEY.clone());
+    let sender = *ALICE_NODE_ID;
+    let timestamp = Timestamp::from(100000);
+    let block_time = zug.params.min_block_time();
+
+    let proposal0 = Proposal::<ClContext> {
+        timestamp,
+        maybe_block: Some(new_payload(false)),
+        maybe_parent_round_id: None,
+        inactive: None,
+    };
+    let proposal2 = Proposal::<ClContext> {
+        timestamp: timestamp + block_time,
+        maybe_block: Some(new_payload(true)),
+        maybe_parent_round_id: Some(0),
+        inactive: Some(Default::default()),
+    };
+    let proposal3 = Proposal::<ClContext> {
+        timestamp: timestamp + block_time + block_time,
+        maybe_block: Some(new_payload(false)),
+        maybe_parent_round_id: Some(2),
+        inactive: Some(Default::default()),
+    };
+    assert!(zug.height_to_round().is_empty());
+
+    // Round 0 is committed.
+    let msg = create_proposal_message(0, &proposal0, &validators, &alice_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    let msg = create_message(&validators, 0, echo(proposal0.hash()), &bob_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    for kp in [&alice_kp, &bob_kp] {
+        let msg = create_message(&validators, 0, vote(true), kp);
+        zug.handle_message(&mut rng, sender, msg, timestamp);
+    }
+    assert_eq!(1, zug.current_round);
+
+    // Round 1 is skipped.
+    for kp in [&alice_kp, &bob_kp] {
+        let msg = create_message(&validators, 1, vote(false), kp);
+        zug.handle_message(&mut rng, sender, msg, timestamp);
+    }
+    assert_eq!(2, zug.current_round);
+
+    // Rounds 2 and 3 are committed, with their proposals at heights 1 and 2.
+    for (round_id, proposal) in [(2, &proposal2), (3, &proposal3)] {
+        let msg = create_proposal_message(round_id, proposal, &validators, &alice_kp);
+        zug.handle_message(&mut rng, sender, msg, proposal.timestamp);
+        let msg = create_message(&validators, round_id, echo(proposal.hash()), &bob_kp);
+        zug.handle_message(&mut rng, sender, msg, proposal.timestamp);
+        for kp in [&alice_kp, &bob_kp] {
+            let msg = create_message(&validators, round_id, vote(true), kp);
+            zug.handle_message(&mut rng, sender, msg, proposal.timestamp);
+        }
+    }
+
+    let expected: BTreeMap<u64, RoundId> = [(0, 0), (1, 2), (2, 3)].into_iter().collect();
+    assert_eq!(expected, zug.height_to_round());
+}
+
+/// Tests that a proposal that was received from two senders while waiting for validation is only
+/// handled once when it turns out to be valid.
+#[test]
+fn zug_resolve_validity_deduplicates_senders() {
+    let mut rng = crate::new_rng();
+    let (weights, validators) = abc_weights(60, 30, 10);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+
+    let mut zug = new_test_zug(weights, vec![], &[alice_idx]);
+
+    let alice_kp = Keypair::from(ALICE_SECRET_KEY.clone());
+    let timestamp = Timestamp::from(100000);
+
+    // This block contains an accusation, so it needs validation.
+    let block = Arc::new(BlockPayload::new(
+        vec![],
+        vec![],
+        vec![CAROL_PUBLIC_KEY.clone()],
+        false,
+    ));
+    let proposal = Proposal::<ClContext> {
+        timestamp,
+        maybe_block: Some(block),
+        maybe_parent_round_id: None,
+        inactive: None,
+    };
+
+    // Alice and Bob both send us the proposal.
+    let mut proposed_blocks = vec![];
+    for sender in [*ALICE_NODE_ID, *BOB_NODE_ID] {
+        let msg = create_proposal_message(0, &proposal, &validators, &alice_kp);
+        let outcomes = zug.handle_message(&mut rng, sender, msg, timestamp);
+        proposed_blocks.extend(outcomes.into_iter().filter_map(|outcome| match outcome {
+            ProtocolOutcome::ValidateConsensusValue { proposed_block, .. } => Some(proposed_block),
+            _ => None,
+        }));
+    }
+    assert_eq!(2, proposed_blocks.len());
+    assert_eq!(proposed_blocks[0], proposed_blocks[1]);
+    assert!(zug.round(0).and_then(Round::proposal).is_none());
+
+    // Once it is valid, the proposal is handled once.
+    let outcomes = zug.resolve_validity(proposed_blocks.remove(0), true, timestamp);
+    let handled_count = outcomes
+        .iter()
+        .filter(|outcome| matches!(outcome, ProtocolOutcome::HandledProposedBlock(_)))
+        .count();
+    assert_eq!(1, handled_count);
+    assert_eq!(
+        Some(&proposal),
+        zug.round(0)
+            .and_then(Round::proposal)
+            .map(HashedProposal::inner)
+    );
+    assert!(zug.proposals_waiting_for_validation.is_empty());
+}
+
+/// Tests that `current_round_blocker` reports what the current round is waiting for.
+#[test]
+fn zug_current_round_blocker() {
+    let mut rng = crate::new_rng();
+    let (weights, validators) = abc_weights(60, 30, 10);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+
+    let alice_kp = Keypair::from(ALICE_SECRET_KEY.clone());
+    let bob_kp = Keypair::from(BOB_SECRET_KEY.clone());
+    let sender = *ALICE_NODE_ID;
+    let timestamp = Timestamp::from(100000);
+
+    let proposal0 = Proposal::<ClContext> {
+        timestamp,
+        maybe_block: Some(new_payload(false)),
+        maybe_parent_round_id: None,
+        inactive: None,
+    };
+    let proposal1 = Proposal::<ClContext> {
+        timestamp,
+        maybe_block: Some(new_payload(true)),
+        maybe_parent_round_id: None,
+        inactive: None,
+    };
+
+    // Alice is the leader in rounds 0 and 1.
+    let mut zug = new_test_zug(weights.clone(), vec![], &[alice_idx, alice_idx]);
+    assert_eq!(RoundBlocker::NoProposal, zug.current_round_blocker());
+
+    // Alice's proposal comes with her echo, but that's not a quorum.
+    let msg = create_proposal_message(0, &proposal0, &validators, &alice_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    assert_eq!(
+        RoundBlocker::AwaitingEchoQuorum,
+        zug.current_round_blocker()
+    );
+
+    // With Bob's echo round 0 is accepted, so round 1 becomes current. A proposal in round 1
+    // without a parent can only be accepted once round 0 is skippable.
+    let msg = create_message(&validators, 0, echo(proposal0.hash()), &bob_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    assert_eq!(1, zug.current_round);
+    assert_eq!(RoundBlocker::NoProposal, zug.current_round_blocker());
+    let msg = create_proposal_message(1, &proposal1, &validators, &alice_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    let msg = create_message(&validators, 1, echo(proposal1.hash()), &bob_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    assert_eq!(1, zug.current_round);
+    assert_eq!(
+        RoundBlocker::AwaitingParentAcceptance,
+        zug.current_round_blocker()
+    );
+
+    // In a new instance, Bob votes to skip round 0, but that's not a quorum.
+    let mut zug = new_test_zug(weights, vec![], &[alice_idx]);
+    let msg = create_message(&validators, 0, vote(false), &bob_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    assert_eq!(RoundBlocker::AwaitingVotes, zug.current_round_blocker());
+}
+
+/// Tests that the correct weight in the intersection of two quorums is positive as long as the
+/// faulty weight is within the FTT, and zero beyond that.
+#[test]
+fn zug_quorum_intersection_margin() {
+    let (weights, validators) = abc_weights(34, 33, 33);
+    let bob_idx = validators.get_index(&*BOB_PUBLIC_KEY).unwrap();
+
+    // With a finality threshold fraction of 1/3 the FTT is 33.
+    let zug = new_test_zug(weights.clone(), vec![], &[bob_idx]);
+    assert_eq!(Weight(33), zug.fault_tolerance_threshold_weight());
+    assert!(zug.quorum_intersection_margin() > Weight(0));
+
+    // Carol's weight is exactly the FTT.
+    let zug = new_test_zug(weights.clone(), vec![CAROL_PUBLIC_KEY.clone()], &[bob_idx]);
+    assert!(zug.quorum_intersection_margin() > Weight(0));
+
+    // Alice's weight exceeds the FTT, so two quorums may intersect only in faulty validators.
+    let zug = new_test_zug(weights, vec![ALICE_PUBLIC_KEY.clone()], &[bob_idx]);
+    assert_eq!(Weight(0), zug.quorum_intersection_margin());
+}
+
+/// Tests that the signature of the echo that accompanies a proposal can't be reused for a vote.
+#[test]
+fn zug_echo_signature_invalid_for_vote() {
+    let mut rng = crate::new_rng();
+    let (weights, validators) = abc_weights(60, 30, 10);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+
+    let mut zug = new_test_zug(weights, vec![], &[alice_idx]);
+
+    let alice_kp = Keypair::from(ALICE_SECRET_KEY.clone());
+    let sender = *ALICE_NODE_ID;
+    let timestamp = Timestamp::from(100000);
+
+    let proposal = Proposal::<ClContext> {
+        timestamp,
+        maybe_block: Some(new_payload(false)),
+        maybe_parent_round_id: None,
+        inactive: None,
+    };
+    let echo_content = Content::Echo(proposal.hash());
+    let echo = create_signed_message(&validators, 0, echo_content, &alice_kp);
+    assert!(echo.verify_signature(&ALICE_PUBLIC_KEY));
+
+    for vote_value in [true, false] {
+        let fake_vote = echo.with(Content::Vote(vote_value), echo.signature);
+        assert!(!fake_vote.verify_signature(&ALICE_PUBLIC_KEY));
+        let msg = SerializedMessage::from_message(&Message::Signed(fake_vote));
+        let outcomes = zug.handle_message(&mut rng, sender, msg, timestamp);
+        assert!(outcomes.contains(&ProtocolOutcome::Disconnect(sender)));
+    }
+}
+
+/// Tests that the participation metrics match the logged participation.
+#[test]
+fn zug_participation_metrics() {
+    let mut rng = crate::new_rng();
+    let (weights, validators) = abc_weights(60, 30, 10);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+
+    let mut zug = new_test_zug(weights, vec![], &[alice_idx]);
+
+    let alice_kp = Keypair::from(ALICE_SECRET_KEY.clone());
+    let bob_kp = Keypair::from(BOB_SECRET_KEY.clone());
+    let carol_kp = Keypair::from(CAROL_SECRET_KEY.clone());
+    let sender = *ALICE_NODE_ID;
+    let timestamp = Timestamp::from(100000);
+
+    // Alice and Bob skip rounds 0 and 1, then Alice and Carol skip rounds 2 to 5.
+    for round_id in 0..6 {
+        let other_kp = if round_id < 2 { &bob_kp } else { &carol_kp };
+        for kp in [&alice_kp, other_kp] {
+            let msg = create_message(&validators, round_id, vote(false), kp);
+            zug.handle_message(&mut rng, sender, msg, timestamp);
+        }
+    }
+
+    // Carol double-signs.
+    let msg = create_message(&validators, 6, echo(ClContext::hash(&[0])), &carol_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    let msg = create_message(&validators, 6, echo(ClContext::hash(&[1])), &carol_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+
+    // Bob is inactive and Carol is faulty.
+    let participation = zug.participation();
+    let metrics = zug.participation_metrics();
+    assert_eq!(
+        ParticipationMetrics {
+            inactive_stake_percent: 30,
+            faulty_stake_percent: 10,
+            inactive_validator_count: 1,
+            faulty_validator_count: 1,
+            current_round: 6,
+        },
+        metrics
+    );
+    assert_eq!(
+        participation.inactive_stake_percent,
+        metrics.inactive_stake_percent
+    );
+    assert_eq!(
+        participation.faulty_stake_percent,
+        metrics.faulty_stake_percent
+    );
+}
+
+/// Tests that a proposal with a block is rejected if its parent is the switch block, i.e. if its
+/// height exceeds the era's end height after the era's minimum duration has passed.
+#[test]
+fn zug_reject_block_after_switch_block() {
+    let mut rng = crate::new_rng();
+    let (weights, validators) = abc_weights(60, 30, 10);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+
+    // Alice is the leader in rounds 0 to 3.
+    let mut zug = new_test_zug(weights, vec![], &[alice_idx; 4]);
+
+    let alice_kp = Keypair::from(ALICE_SECRET_KEY.clone());
+    let bob_kp = Keypair::from(BOB_SECRET_KEY.clone());
+    let sender = *ALICE_NODE_ID;
+    let block_time = zug.params.min_block_time();
+    let mut timestamp = Timestamp::from(100000);
+
+    // The era has a minimum height of 3, so the proposal in round 2 is the switch block.
+    for round_id in 0..3 {
+        let proposal = Proposal::<ClContext> {
+            timestamp,
+            maybe_block: Some(new_payload(false)),
+            maybe_parent_round_id: round_id.checked_sub(1),
+            inactive: (round_id > 0).then(Default::default),
+        };
+        let msg = create_proposal_message(round_id, &proposal, &validators, &alice_kp);
+        zug.handle_message(&mut rng, sender, msg, timestamp);
+        let msg = create_message(&validators, round_id, echo(proposal.hash()), &bob_kp);
+        zug.handle_message(&mut rng, sender, msg, timestamp);
+        assert!(zug.has_accepted_proposal(round_id));
+        timestamp += block_time;
+    }
+    assert!(zug.accepted_switch_block(2));
+
+    // A block at height 3 is rejected.
+    let proposal3 = Proposal::<ClContext> {
+        timestamp,
+        maybe_block: Some(new_payload(true)),
+        maybe_parent_round_id: Some(2),
+        inactive: Some(Default::default()),
+    };
+    let msg = create_proposal_message(3, &proposal3, &validators, &alice_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    assert!(zug.round(3).and_then(Round::proposal).is_none());
+}
+
+/// Tests that `protocol_stats` counts the rounds removed from memory in evidence-only mode, and
+/// reports the number of retained rounds.
+#[test]
+fn zug_protocol_stats_pruned_rounds() {
+    let mut rng = crate::new_rng();
+    let (weights, validators) = abc_weights(60, 30, 10);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+
+    // Alice is the leader in rounds 0 to 4.
+    let mut zug = new_test_zug(weights, vec![], &[alice_idx; 5]);
+
+    let alice_kp = Keypair::from(ALICE_SECRET_KEY.clone());
+    let bob_kp = Keypair::from(BOB_SECRET_KEY.clone());
+    let sender = *ALICE_NODE_ID;
+    let timestamp = Timestamp::from(100000);
+
+    let proposal0 = Proposal::<ClContext> {
+        timestamp,
+        maybe_block: Some(new_payload(false)),
+        maybe_parent_round_id: None,
+        inactive: None,
+    };
+    let proposal4 = Proposal::<ClContext> {
+        timestamp: timestamp + zug.params.min_block_time(),
+        maybe_block: Some(new_payload(true)),
+        maybe_parent_round_id: Some(0),
+        inactive: Some(Default::default()),
+    };
+
+    // Round 0 is committed and rounds 1 to 3 are skipped.
+    let msg = create_proposal_message(0, &proposal0, &validators, &alice_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    let msg = create_message(&validators, 0, echo(proposal0.hash()), &bob_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    for round_id in 0..4 {
+        for kp in [&alice_kp, &bob_kp] {
+            let msg = create_message(&validators, round_id, vote(round_id == 0), kp);
+            zug.handle_message(&mut rng, sender, msg, timestamp);
+        }
+    }
+    assert_eq!(4, zug.current_round);
+
+    // Once round 4 is finalized, the skipped rounds' proposals are dropped, but the rounds are
+    // retained.
+    let now = proposal4.timestamp;
+    let msg = create_proposal_message(4, &proposal4, &validators, &alice_kp);
+    zug.handle_message(&mut rng, sender, msg, now);
+    let msg = create_message(&validators, 4, echo(proposal4.hash()), &bob_kp);
+    zug.handle_message(&mut rng, sender, msg, now);
+    for kp in [&alice_kp, &bob_kp] {
+        let msg = create_message(&validators, 4, vote(true), kp);
+        zug.handle_message(&mut rng, sender, msg, now);
+    }
+    assert_eq!(5, zug.first_non_finalized_round_id);
+    let stats = zug.protocol_stats();
+    let retained_rounds = zug.rounds.len() as u64;
+    assert!(retained_rounds >= 5);
+    assert_eq!(0, stats.pruned_rounds);
+    assert_eq!(retained_rounds, stats.retained_rounds);
+
+    // When the era is no longer current, all rounds are removed.
+    zug.set_evidence_only();
+    let stats = zug.protocol_stats();
+    assert_eq!(retained_rounds, stats.pruned_rounds);
+    assert_eq!(0, stats.retained_rounds);
+}
+
+/// Tests that the round included in sync requests is selected according to the configured
+/// strategy.
+#[test]
+fn zug_sync_round_strategy() {
+    let mut rng = crate::new_rng();
+    let (weights, validators) = abc_weights(60, 30, 10);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+
+    let mut zug = new_test_zug(weights, vec![], &[alice_idx]);
+
+    let alice_kp = Keypair::from(ALICE_SECRET_KEY.clone());
+    let bob_kp = Keypair::from(BOB_SECRET_KEY.clone());
+    let sender = *ALICE_NODE_ID;
+    let timestamp = Timestamp::from(100000);
+
+    // Rounds 0 to 2 are skipped, so round 3 is current and none are finalized.
+    for round_id in 0..3 {
+        for kp in [&alice_kp, &bob_kp] {
+            let msg = create_message(&validators, round_id, vote(false), kp);
+            zug.handle_message(&mut rng, sender, msg, timestamp);
+        }
+    }
+    assert_eq!(3, zug.current_round);
+    assert_eq!(0, zug.first_non_finalized_round_id);
+
+    assert_eq!(SyncRoundStrategy::Random, zug.config.sync_round_strategy);
+    for _ in 0..10 {
+        assert!(zug.sync_round_id(&mut rng) <= 3);
+    }
+
+    zug.config.sync_round_strategy = SyncRoundStrategy::LowestPending;
+    assert_eq!(0, zug.sync_round_id(&mut rng));
+
+    zug.config.sync_round_strategy = SyncRoundStrategy::Current;
+    assert_eq!(3, zug.sync_round_id(&mut rng));
+}
+
+/// Tests that `accepted_block_value` returns the block of an accepted proposal, and `None` for a
+/// dummy proposal or a round without an accepted proposal.
+#[test]
+fn zug_accepted_block_value() {
+    let mut rng = crate::new_rng();
+    let (weights, validators) = abc_weights(60, 30, 10);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+
+    // Alice is the leader in rounds 0 to 3.
+    let mut zug = new_test_zug(weights, vec![], &[alice_idx; 4]);
+
+    let alice_kp = Keypair::from(ALICE_SECRET_KEY.clone());
+    let bob_kp = Keypair::from(BOB_SECRET_KEY.clone());
+    let sender = *ALICE_NODE_ID;
+    let block_time = zug.params.min_block_time();
+    let mut timestamp = Timestamp::from(100000);
+
+    // The era has a minimum height of 3, so the proposal in round 2 is the switch block, and
+    // round 3 can only have a dummy proposal.
+    let mut blocks = vec![];
+    for round_id in 0..4 {
+        let maybe_block = (round_id < 3).then(|| new_payload(round_id != 1));
+        let proposal = Proposal::<ClContext> {
+            timestamp,
+            maybe_block: maybe_block.clone(),
+            maybe_parent_round_id: round_id.checked_sub(1),
+            inactive: (round_id > 0 && round_id < 3).then(Default::default),
+        };
+        assert_eq!(None, zug.accepted_block_value(round_id));
+        let msg = create_proposal_message(round_id, &proposal, &validators, &alice_kp);
+        zug.handle_message(&mut rng, sender, msg, timestamp);
+        let msg = create_message(&validators, round_id, echo(proposal.hash()), &bob_kp);
+        zug.handle_message(&mut rng, sender, msg, timestamp);
+        assert!(zug.has_accepted_proposal(round_id));
+        blocks.push(maybe_block);
+        timestamp += block_time;
+    }
+
+    for (round_id, maybe_block) in (0..).zip(blocks) {
+        assert_eq!(maybe_block, zug.accepted_block_value(round_id));
+    }
+    assert_eq!(None, zug.accepted_block_value(3));
+    assert_eq!(None, zug.accepted_block_value(4));
+}
+
+/// Tests that a sync request referring to a round far ahead of ours makes us send a bounded burst
+/// of sync requests, at most once per sync state interval.
+#[test]
+fn zug_sync_request_from_future_round() {
+    let mut rng = crate::new_rng();
+    let (weights, validators) = abc_weights(60, 30, 10);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+
+    let mut zug = new_test_zug(weights, vec![], &[alice_idx]);
+
+    let sender = *ALICE_NODE_ID;
+    let interval = zug.config.sync_state_interval.expect("sync state interval");
+    let mut timestamp = Timestamp::from(100000);
+
+    let mut sync_request_for = |zug: &mut Zug<ClContext>, round_id, timestamp| {
+        let msg = SyncRequest::<ClContext>::new_empty_round(
+            round_id,
+            ValidatorIndex(0),
+            0,
+            0,
+            *zug.instance_id(),
+        );
+        let (mut outcomes, _) = zug.handle_request_message(
+            &mut rng,
+            sender,
+            SerializedMessage::from_message(&msg),
+            timestamp,
+        );
+        remove_requests_to_random(&mut outcomes)
+            .into_iter()
+            .map(|request| request.round_id)
+            .collect::<Vec<_>>()
+    };
+
+    // The peer is in round 50. We request our current round 0 and the next nine.
+    assert_eq!(
+        (0..10).collect::<Vec<_>>(),
+        sync_request_for(&mut zug, 50, timestamp)
+    );
+
+    // Another request right away doesn't trigger another burst.
+    assert!(sync_request_for(&mut zug, 50, timestamp).is_empty());
+
+    // After the interval, a peer in round 3 makes us request rounds 0 to 3.
+    timestamp += interval;
+    assert_eq!(vec![0, 1, 2, 3], sync_request_for(&mut zug, 3, timestamp));
+
+    // A request about our current round doesn't trigger a burst.
+    timestamp += interval;
+    assert!(sync_request_for(&mut zug, 0, timestamp).is_empty());
+}
+
+/// Tests that the sync peer timer is rescheduled with a random jitter.
+#[test]
+fn zug_sync_timer_jitter() {
+    let mut rng = crate::new_rng();
+    let (weights, validators) = abc_weights(60, 30, 10);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+    let mut zug = new_test_zug(weights, vec![], &[alice_idx]);
+    let interval = zug.config.sync_state_interval.expect("sync state interval");
+    let mut timestamp = zug.params.start_timestamp();
+
+    let mut delays = BTreeSet::new();
+    for _ in 0..10 {
+        let outcomes = zug.handle_timer(timestamp, timestamp, TIMER_ID_SYNC_PEER, &mut rng);
+        let delay = expect_sync_timer(&outcomes, timestamp, interval);
+        delays.insert(delay);
+        timestamp += delay;
+    }
+    assert!(delays.len() > 1, "sync intervals don't vary: {:?}", delays);
+}
+
+/// Tests that we refuse to sign a message that conflicts with one we already signed.
+#[test]
+fn zug_refuse_conflicting_own_message() {
+    let (weights, validators) = abc_weights(60, 30, 10);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+    let bob_idx = validators.get_index(&*BOB_PUBLIC_KEY).unwrap();
+    let mut zug = new_test_zug(weights, vec![], &[alice_idx]);
+    let bob_kp = Keypair::from(BOB_SECRET_KEY.clone());
+    let timestamp = Timestamp::from(100000);
+
+    // We are Bob.
+    let dir = tempdir().unwrap();
+    zug.open_wal(dir.path().join("wal"), timestamp);
+    zug.activate_validator(BOB_PUBLIC_KEY.clone(), bob_kp, timestamp, None);
+
+    let hash0 = <ClContext as Context>::hash(&[0]);
+    let hash1 = <ClContext as Context>::hash(&[1]);
+
+    assert!(zug.create_message(0, echo(hash0)).is_some());
+    assert!(zug.create_message(0, echo(hash1)).is_none());
+    assert!(zug.create_message(0, echo(hash0)).is_none());
+    assert!(zug.create_message(0, vote(false)).is_some());
+    assert!(zug.create_message(0, vote(true)).is_none());
+
+    let round = zug.round(0).expect("round 0 should exist");
+    assert!(round.contains(&echo(hash0), bob_idx));
+    assert!(!round.contains(&echo(hash1), bob_idx));
+    assert!(round.contains(&vote(false), bob_idx));
+    assert!(!round.contains(&vote(true), bob_idx));
+}
+
+/// Tests that a sync response only contains as many echoes as the requester needs for a quorum.
+#[test]
+fn zug_sync_response_limits_echoes_to_quorum() {
+    let mut rng = crate::new_rng();
+    let (weights, validators) = abc_weights(60, 30, 10);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+    let bob_idx = validators.get_index(&*BOB_PUBLIC_KEY).unwrap();
+    let carol_idx = validators.get_index(&*CAROL_PUBLIC_KEY).unwrap();
+
+    let mut zug = new_test_zug(weights, vec![], &[alice_idx]);
+
+    let alice_kp = Keypair::from(ALICE_SECRET_KEY.clone());
+    let bob_kp = Keypair::from(BOB_SECRET_KEY.clone());
+    let carol_kp = Keypair::from(CAROL_SECRET_KEY.clone());
+    let sender = *ALICE_NODE_ID;
+    let timestamp = Timestamp::from(100000);
+
+    let proposal0 = Proposal::<ClContext> {
+        timestamp,
+        maybe_block: Some(new_payload(false)),
+        maybe_parent_round_id: None,
+        inactive: None,
+    };
+    let hash0 = proposal0.hash();
+
+    // We have echoes from everyone.
+    let msg = create_proposal_message(0, &proposal0, &validators, &alice_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    let msg = create_message(&validators, 0, echo(hash0), &bob_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    let msg = create_message(&validators, 0, echo(hash0), &carol_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    assert_eq!(3, zug.round(0).unwrap().echoes()[&hash0].len());
+
+    let mut echo_sigs_for = |zug: &mut Zug<ClContext>, known_echoes: Vec<ValidatorIndex>| {
+        let msg = SyncRequest::<ClContext> {
+            round_id: 0,
+            proposal_hash: Some(hash0),
+            has_proposal: true,
+            first_validator_idx: alice_idx,
+            echoes: zug.validator_bit_field(alice_idx, known_echoes.into_iter()),
+            true_votes: 0,
+            false_votes: 0,
+            active: zug
+                .validator_bit_field(alice_idx, vec![alice_idx, bob_idx, carol_idx].into_iter()),
+            faulty: 0,
+            instance_id: *zug.instance_id(),
+        };
+        let (_, response) = zug.handle_request_message(
+            &mut rng,
+            sender,
+            SerializedMessage::from_message(&msg),
+            timestamp,
+        );
+        match response.expect("response").deserialize_expect() {
+            Message::SyncResponse(sync_response) => sync_response.echo_sigs,
+            result => panic!("unexpected message: {:?}", result),
+        }
+    };
+
+    // Starting with Alice, any two echoes are a quorum, so the third one is not sent.
+    let echo_sigs = echo_sigs_for(&mut zug, vec![]);
+    assert_eq!(2, echo_sigs.len());
+    assert!(echo_sigs.contains_key(&alice_idx));
+
+    // If they already have Alice's and Bob's echoes, they don't need Carol's.
+    assert!(echo_sigs_for(&mut zug, vec![alice_idx, bob_idx]).is_empty());
+
+    // With only Carol's echo they are missing Alice's for a quorum.
+    let echo_sigs = echo_sigs_for(&mut zug, vec![carol_idx]);
+    assert_eq!(1, echo_sigs.len());
+    assert!(echo_sigs.contains_key(&alice_idx));
+}
+
+/// Tests that a sync response never contains more signed messages than configured.
+#[test]
+fn zug_sync_response_max_messages() {
+    let mut rng = crate::new_rng();
+    let (weights, validators) = abc_weights(60, 30, 10);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+
+    let mut zug = new_test_zug(weights, vec![], &[alice_idx]);
+
+    let alice_kp = Keypair::from(ALICE_SECRET_KEY.clone());
+    let bob_kp = Keypair::from(BOB_SECRET_KEY.clone());
+    let carol_kp = Keypair::from(CAROL_SECRET_KEY.clone());
+    let sender = *ALICE_NODE_ID;
+    let timestamp = Timestamp::from(100000);
+
+    let proposal0 = Proposal::<ClContext> {
+        timestamp,
+        maybe_block: Some(new_payload(false)),
+        maybe_parent_round_id: None,
+        inactive: None,
+    };
+    let hash0 = proposal0.hash();
+
+    // We have all echoes and votes in round 0, and Carol double-signs.
+    let msg = create_proposal_message(0, &proposal0, &validators, &alice_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    for kp in [&bob_kp, &carol_kp] {
+        let msg = create_message(&validators, 0, echo(hash0), kp);
+        zug.handle_message(&mut rng, sender, msg, timestamp);
+    }
+    for kp in [&alice_kp, &bob_kp, &carol_kp] {
+        let msg = create_message(&validators, 0, vote(true), kp);
+        zug.handle_message(&mut rng, sender, msg, timestamp);
+    }
+    let msg = create_message(&validators, 0, vote(false), &carol_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+
+    let mut message_count = |zug: &mut Zug<ClContext>, max_messages| {
+        zug.config.max_sync_response_messages = max_messages;
+        let msg = SyncRequest::<ClContext>::new_empty_round(0, alice_idx, 0, 0, *zug.instance_id());
+        let (_, response) = zug.handle_request_message(
+            &mut rng,
+            sender,
+            SerializedMessage::from_message(&msg),
+            timestamp,
+        );
+        match response.expect("response").deserialize_expect() {
+            Message::SyncResponse(sync_response) => [
+                sync_response.echo_sigs.len(),
+                sync_response.true_vote_sigs.len(),
+                sync_response.false_vote_sigs.len(),
+                sync_response.signed_messages.len(),
+                sync_response.evidence.len(),
+            ]
+            .iter()
+            .sum::<usize>(),
+            result => panic!("unexpected message: {:?}", result),
+        }
+    };
+
+    let unlimited = message_count(&mut zug, 1000);
+    assert!(
+        unlimited > 3,
+        "expected more than 3 messages, got {}",
+        unlimited
+    );
+    for max_messages in 0..4 {
+        assert!(message_count(&mut zug, max_messages) <= max_messages as usize);
+    }
+}
+
+/// Tests that the leader's echo, which is included if the requester is missing the proposal,
+/// counts against the sync response limit.
+#[test]
+fn zug_sync_response_max_messages_includes_leader_echo() {
+    let mut rng = crate::new_rng();
+    let keys: Vec<Arc<SecretKey>> = (0u8..200)
+        .map(|i| Arc::new(SecretKey::ed25519_from_bytes([i; SecretKey::ED25519_LENGTH]).unwrap()))
+        .collect();
+    let weights: Vec<(PublicKey, U512)> = keys
+        .iter()
+        .map(|sk| (PublicKey::from(&**sk), U512::from(100)))
+        .collect();
+    let validators = common::validators::<ClContext>(
+        &Default::default(),
+        &Default::default(),
+        weights.iter().cloned().collect(),
+    );
+    let keypair = |v_idx: ValidatorIndex| {
+        let public_key = validators.id(v_idx).unwrap();
+        let sk = keys
+            .iter()
+            .find(|sk| PublicKey::from(&***sk) == *public_key)
+            .unwrap();
+        Keypair::from(sk.clone())
+    };
+    let leader_idx = ValidatorIndex(0);
+
+    let mut zug = new_test_zug(weights, vec![], &[leader_idx]);
+    let sender = *ALICE_NODE_ID;
+    let timestamp = Timestamp::from(100000);
+
+    let proposal0 = Proposal::<ClContext> {
+        timestamp,
+        maybe_block: Some(new_payload(false)),
+        maybe_parent_round_id: None,
+        inactive: None,
+    };
+    let hash0 = proposal0.hash();
+
+    // We have the proposal with the leader's echo, and five more echoes.
+    let msg = create_proposal_message(0, &proposal0, &validators, &keypair(leader_idx));
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+    for v_idx in [1, 2, 3, 198, 199].map(ValidatorIndex) {
+        let msg = create_message(&validators, 0, echo(hash0), &keypair(v_idx));
+        zug.handle_message(&mut rng, sender, msg, timestamp);
+    }
+
+    // The requester knows the hash but not the proposal, and their bit field doesn't cover the
+    // leader.
+    zug.config.max_sync_response_messages = 2;
+    let msg = SyncRequest::<ClContext> {
+        round_id: 0,
+        proposal_hash: Some(hash0),
+        has_proposal: false,
+        first_validator_idx: ValidatorIndex(1),
+        echoes: 0,
+        true_votes: 0,
+        false_votes: 0,
+        active: 0,
+        faulty: 0,
+        instance_id: *zug.instance_id(),
+    };
+    let (_, response) = zug.handle_request_message(
+        &mut rng,
+        sender,
+        SerializedMessage::from_message(&msg),
+        timestamp,
+    );
+    match response.expect("response").deserialize_expect() {
+        Message::SyncResponse(sync_response) => {
+            assert_eq!(
+                vec![leader_idx, ValidatorIndex(1)],
+                sync_response.echo_sigs.keys().copied().collect::<Vec<_>>()
+            );
+        }
+        result => panic!("unexpected message: {:?}", result),
+    }
+
+    // Now the requester's bit field wraps around and covers the leader, but the echoes from
+    // validators 198 and 199 come first in it. The leader's echo must still be included.
+    let msg = SyncRequest::<ClContext> {
+        first_validator_idx: ValidatorIndex(198),
+        ..msg
+    };
+    let (_, response) = zug.handle_request_message(
+        &mut rng,
+        sender,
+        SerializedMessage::from_message(&msg),
+        timestamp,
+    );
+    match response.expect("response").deserialize_expect() {
+        Message::SyncResponse(sync_response) => {
+            assert_eq!(
+                vec![leader_idx, ValidatorIndex(198)],
+                sync_response.echo_sigs.keys().copied().collect::<Vec<_>>()
+            );
+        }
+        result => panic!("unexpected message: {:?}", result),
+    }
+}
+
+/// Tests that a validator can't make us track an unbounded number of echoed hashes.
+#[test]
+fn zug_echo_flood_is_bounded() {
+    let mut rng = crate::new_rng();
+    let (weights, validators) = abc_weights(60, 30, 10);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+    let bob_idx = validators.get_index(&*BOB_PUBLIC_KEY).unwrap();
+
+    let mut zug = new_test_zug(weights, vec![], &[alice_idx]);
+
+    let alice_kp = Keypair::from(ALICE_SECRET_KEY.clone());
+    let bob_kp = Keypair::from(BOB_SECRET_KEY.clone());
+    let carol_kp = Keypair::from(CAROL_SECRET_KEY.clone());
+    let sender = *ALICE_NODE_ID;
+    let timestamp = Timestamp::from(100000);
+
+    let proposal0 = Proposal::<ClContext> {
+        timestamp,
+        maybe_block: Some(new_payload(false)),
+        maybe_parent_round_id: None,
+        inactive: None,
+    };
+    let hash0 = proposal0.hash();
+
+    let msg = create_proposal_message(0, &proposal0, &validators, &alice_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+
+    // Bob echoes lots of hashes for proposals we never receive.
+    for i in 0u8..20 {
+        let hash = <ClContext as Context>::hash(&[i]);
+        let msg = create_message(&validators, 0, echo(hash), &bob_kp);
+        zug.handle_message(&mut rng, sender, msg, timestamp);
+    }
+    let msg = create_message(&validators, 0, echo(hash0), &carol_kp);
+    zug.handle_message(&mut rng, sender, msg, timestamp);
+
+    // Bob is faulty, and only the proposal's hash is still tracked.
+    assert!(zug.faults.contains_key(&bob_idx));
+    let round = zug.round(0).expect("round 0 should exist");
+    assert_eq!(
+        vec![hash0],
+        round.echoes().keys().copied().collect::<Vec<_>>()
+    );
+    assert_eq!(Some(hash0), round.quorum_echoes());
+}
+
+/// Tests that `serialized_size` matches the actual serialization for every message variant.
+#[test]
+fn zug_message_serialized_size() {
+    let (weights, validators) = abc_weights(60, 30, 10);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+    let zug = new_test_zug(weights, vec![], &[alice_idx]);
+    let instance_id = *zug.instance_id();
+
+    let alice_kp = Keypair::from(ALICE_SECRET_KEY.clone());
+    let proposal = Proposal::<ClContext> {
+        timestamp: Timestamp::from(100000),
+        maybe_block: Some(new_payload(false)),
+        maybe_parent_round_id: None,
+        inactive: None,
+    };
+    let echo_msg = create_signed_message(&validators, 0, echo(proposal.hash()), &alice_kp);
+    let other_echo = create_signed_message(
+        &validators,
+        0,
+        Content::Echo(<ClContext as Context>::hash(&[1])),
+        &alice_kp,
+    );
+
+    let messages = vec![
+        Message::SyncResponse(SyncResponse {
+            round_id: 0,
+            proposal_or_hash: Some(Either::Left(proposal.clone())),
+            echo_sigs: iter::once((alice_idx, echo_msg.signature)).collect(),
+            true_vote_sigs: BTreeMap::new(),
+            false_vote_sigs: BTreeMap::new(),
+            signed_messages: vec![echo_msg.clone()],
+            evidence: Vec::new(),
+            instance_id,
+        }),
+        Message::Proposal {
+            round_id: 0,
+            instance_id,
+            proposal,
+            echo: echo_msg.clone(),
+        },
+        Message::Signed(echo_msg.clone()),
+        Message::Evidence(echo_msg, other_echo.content, other_echo.signature),
+    ];
+    for msg in messages {
+        assert_eq!(
+            SerializedMessage::from_message(&msg).as_raw().len(),
+            msg.serialized_size(),
+            "wrong size for {:?}",
+            msg
+        );
+    }
+}
+
+/// Tests that a paused leader doesn't propose, but still handles incoming messages, and proposes
+/// once unpaused.
+#[test]
+fn zug_paused_leader_does_not_propose() {
+    let mut rng = crate::new_rng();
+    let (weights, validators) = abc_weights(60, 30, 10);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+    let bob_idx = validators.get_index(&*BOB_PUBLIC_KEY).unwrap();
+
+    // The first round leader is Alice.
+    let mut zug = new_test_zug(weights, vec![], &[alice_idx]);
+
+    let alice_kp = Keypair::from(ALICE_SECRET_KEY.clone());
+    let bob_kp = Keypair::from(BOB_SECRET_KEY.clone());
+    let sender = *BOB_NODE_ID;
+    let timestamp = Timestamp::from(100000);
+
+    // We are Alice, but paused.
+    let dir = tempdir().unwrap();
+    zug.open_wal(dir.path().join("wal"), timestamp);
+    zug.activate_validator(ALICE_PUBLIC_KEY.clone(), alice_kp, timestamp, None);
+    zug.set_paused(true, timestamp);
+
+    // Bob's echo is recorded. We request a block, but we don't sign and gossip a proposal with it.
+    let hash = <ClContext as Context>::hash(&[1]);
+    let msg = create_message(&validators, 0, echo(hash), &bob_kp);
+    let mut outcomes = zug.handle_message(&mut rng, sender, msg, timestamp);
+    assert!(zug.round(0).unwrap().contains(&echo(hash), bob_idx));
+    let block_context = remove_create_new_block(&mut outcomes);
+    let proposed_block = ProposedBlock::new(new_payload(false), block_context);
+    let mut outcomes = zug.propose(proposed_block, timestamp);
+    let gossip = remove_gossip(&validators, &mut outcomes);
+    assert!(gossip.is_empty(), "unexpected gossip: {:?}", gossip);
+    assert!(!zug.round(0).unwrap().has_proposal());
+
+    // Once unpaused, we request a block again and propose it.
+    let mut outcomes = zug.set_paused(false, timestamp);
+    let block_context = remove_create_new_block(&mut outcomes);
+    assert_eq!(timestamp, block_context.timestamp());
+    let proposed_block = ProposedBlock::new(new_payload(false), block_context);
+    let mut outcomes = zug.propose(proposed_block, timestamp);
+    let gossip = remove_gossip(&validators, &mut outcomes);
+    assert!(
+        gossip
+            .iter()
+            .any(|msg| matches!(msg, Message::Proposal { round_id: 0, .. })),
+        "missing proposal: {:?}",
+        gossip
+    );
+}
+
+/// Tests that `latest_finalized` returns the latest finalized proposal with a block.
+#[test]
+fn zug_latest_finalized() {
+    let mut rng = crate::new_rng();
+    let (weights, validators) = abc_weights(60, 30, 10);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+
+    // Alice is the leader in rounds 0 to 3.
+    let mut zug = new_test_zug(weights, vec![], &[alice_idx; 4]);
+
+    let alice_kp = Keypair::from(ALICE_SECRET_KEY.clone());
+    let bob_kp = Keypair::from(BOB_SECRET_KEY.clone());
+    let sender = *ALICE_NODE_ID;
+    let block_time = zug.params.min_block_time();
+    let mut timestamp = Timestamp::from(100000);
+
+    // The era has a minimum height of 3, so the proposal in round 2 is the switch block, and
+    // round 3 can only have a dummy proposal.
+    let mut timestamps = vec![];
+    for round_id in 0..4 {
+        let proposal = Proposal::<ClContext> {
+            timestamp,
+            maybe_block: (round_id < 3).then(|| new_payload(false)),
+            maybe_parent_round_id: round_id.checked_sub(1),
+            inactive: (round_id > 0 && round_id < 3).then(Default::default),
+        };
+        let msg = create_proposal_message(round_id, &proposal, &validators, &alice_kp);
+        zug.handle_message(&mut rng, sender, msg, timestamp);
+        let msg = create_message(&validators, round_id, echo(proposal.hash()), &bob_kp);
+        zug.handle_message(&mut rng, sender, msg, timestamp);
+        timestamps.push(timestamp);
+        timestamp += block_time;
+    }
+    assert_eq!(None, zug.latest_finalized());
+
+    let mut commit = |zug: &mut Zug<ClContext>, round_id| {
+        for kp in [&alice_kp, &bob_kp] {
+            let msg = create_message(&validators, round_id, vote(true), kp);
+            zug.handle_message(&mut rng, sender, msg, timestamp);
+        }
+    };
+
+    // Committing round 1 finalizes rounds 0 and 1.
+    commit(&mut zug, 1);
+    assert_eq!(
+        Some((1, 1, timestamps[1], ALICE_PUBLIC_KEY.clone())),
+        zug.latest_finalized()
+    );
+
+    // Round 3 has no block, so the switch block in round 2 is the latest one.
+    commit(&mut zug, 3);
+    assert_eq!(
+        Some((2, 2, timestamps[2], ALICE_PUBLIC_KEY.clone())),
+        zug.latest_finalized()
+    );
+}
+
+/// Tests that the number of rounds after which a validator is considered inactive is configurable.
+#[test]
+fn zug_participation_grace_rounds() {
+    let mut rng = crate::new_rng();
+    let (weights, validators) = abc_weights(60, 30, 10);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+    let bob_idx = validators.get_index(&*BOB_PUBLIC_KEY).unwrap();
+
+    let mut zug = new_test_zug(weights, vec![], &[alice_idx]);
+
+    let alice_kp = Keypair::from(ALICE_SECRET_KEY.clone());
+    let bob_kp = Keypair::from(BOB_SECRET_KEY.clone());
+    let carol_kp = Keypair::from(CAROL_SECRET_KEY.clone());
+    let sender = *ALICE_NODE_ID;
+    let timestamp = Timestamp::from(100000);
+
+    // Alice and Bob skip round 0, then Alice and Carol skip rounds 1 and 2.
+    for round_id in 0..3 {
+        let other_kp = if round_id < 1 { &bob_kp } else { &carol_kp };
+        for kp in [&alice_kp, other_kp] {
+            let msg = create_message(&validators, round_id, vote(false), kp);
+            zug.handle_message(&mut rng, sender, msg, timestamp);
+        }
+    }
+    assert_eq!(3, zug.current_round);
+
+    // Bob was last seen three rounds ago.
+    zug.config.participation_grace_rounds = 2;
+    let expected: ValidatorMap<_> = validators
+        .enumerate_ids()
+        .map(|(idx, _)| (idx == bob_idx).then_some(ParticipationStatus::LastSeenInRound(0)))
+        .collect();
+    assert_eq!(expected, ParticipationStatus::for_all(&zug));
+
+    zug.config.participation_grace_rounds = 4;
+    let expected: ValidatorMap<_> = vec![None; 3].into();
+    assert_eq!(expected, ParticipationStatus::for_all(&zug));
+}
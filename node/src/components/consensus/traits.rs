@@ -12,6 +12,10 @@ impl<VID> ValidatorIdT for VID where VID: Eq + Ord + Clone + Debug + Hash + Send
 {}
 
 /// The consensus value type, e.g. a list of transactions.
+///
+/// The serialization must be deterministic: Equal values must always serialize to the same bytes,
+/// since proposals are identified by the hash of their serialization. In particular, values must
+/// not contain collections with an unspecified iteration order, like `HashMap`.
 pub trait ConsensusValueT:
     Eq + Clone + Debug + Display + Hash + Serialize + DeserializeOwned + Send + DataSize
 {
@@ -243,6 +243,12 @@ impl<T> ValidatorMap<T> {
         self.0.get(idx.0 as usize)
     }
 
+    /// Returns a mutable reference to the value for the given validator, or `None` if the index is
+    /// out of range.
+    pub fn get_mut(&mut self, idx: ValidatorIndex) -> Option<&mut T> {
+        self.0.get_mut(idx.0 as usize)
+    }
+
     /// Returns the number of values. This must equal the number of validators.
     pub fn len(&self) -> usize {
         self.0.len()
@@ -24,7 +24,7 @@ use crate::{
     components::{Component, ComponentState, InitializedComponent, PortBoundComponent},
     effect::{
         announcements::ControlAnnouncement,
-        diagnostics_port::DumpConsensusStateRequest,
+        diagnostics_port::{ConsensusControlRequest, DumpConsensusStateRequest},
         requests::{NetworkInfoRequest, SetNodeStopRequest},
         EffectBuilder, EffectExt, Effects,
     },
@@ -105,6 +105,7 @@ impl<REv> Component<REv> for DiagnosticsPort
 where
     REv: From<Event>
         + From<DumpConsensusStateRequest>
+        + From<ConsensusControlRequest>
         + From<ControlAnnouncement>
         + From<NetworkInfoRequest>
         + From<SetNodeStopRequest>
@@ -159,6 +160,7 @@ impl<REv> InitializedComponent<REv> for DiagnosticsPort
 where
     REv: From<Event>
         + From<DumpConsensusStateRequest>
+        + From<ConsensusControlRequest>
         + From<ControlAnnouncement>
         + From<NetworkInfoRequest>
         + From<SetNodeStopRequest>
@@ -183,6 +185,7 @@ impl<REv> PortBoundComponent<REv> for DiagnosticsPort
 where
     REv: From<Event>
         + From<DumpConsensusStateRequest>
+        + From<ConsensusControlRequest>
         + From<ControlAnnouncement>
         + From<NetworkInfoRequest>
         + From<SetNodeStopRequest>
@@ -92,6 +92,26 @@ pub(super) enum Action {
         /// Era to dump. If omitted, dumps the latest era.
         era: Option<u64>,
     },
+    /// Enable or disable syncing the consensus protocol state with random peers.
+    ///
+    /// Only supported by the Zug consensus protocol.
+    SetConsensusSync {
+        /// Whether to sync with random peers, `true` or `false`.
+        #[structopt(parse(try_from_str))]
+        enabled: bool,
+        /// Era to change. If omitted, changes the latest era.
+        era: Option<u64>,
+    },
+    /// Make the given consensus round the current one right away, without waiting for the
+    /// earlier rounds to end.
+    ///
+    /// Only supported by the Zug consensus protocol.
+    ForceStartRound {
+        /// Round to start.
+        round: u32,
+        /// Era to change. If omitted, changes the latest era.
+        era: Option<u64>,
+    },
     /// Dump the event queues.
     DumpQueues,
     /// Get detailed networking insights.
@@ -142,5 +162,30 @@ mod tests {
 
         let cmd = Command::from_line("dump-queues").expect("command parsing failed");
         assert!(matches!(cmd.action, Action::DumpQueues));
+
+        let cmd = Command::from_line("set-consensus-sync false").expect("command parsing failed");
+        assert!(matches!(
+            cmd.action,
+            Action::SetConsensusSync { enabled, era } if !enabled && era.is_none()
+        ));
+
+        let cmd =
+            Command::from_line("set-consensus-sync true 123").expect("command parsing failed");
+        assert!(matches!(
+            cmd.action,
+            Action::SetConsensusSync { enabled, era } if enabled && era == Some(123)
+        ));
+
+        let cmd = Command::from_line("force-start-round 7").expect("command parsing failed");
+        assert!(matches!(
+            cmd.action,
+            Action::ForceStartRound { round, era } if round == 7 && era.is_none()
+        ));
+
+        let cmd = Command::from_line("force-start-round 7 123").expect("command parsing failed");
+        assert!(matches!(
+            cmd.action,
+            Action::ForceStartRound { round, era } if round == 7 && era == Some(123)
+        ));
     }
 }
@@ -32,7 +32,9 @@ use crate::{
     components::consensus::EraDump,
     effect::{
         announcements::{ControlAnnouncement, QueueDumpFormat},
-        diagnostics_port::DumpConsensusStateRequest,
+        diagnostics_port::{
+            ConsensusControlCommand, ConsensusControlRequest, DumpConsensusStateRequest,
+        },
         requests::{NetworkInfoRequest, SetNodeStopRequest},
         EffectBuilder,
     },
@@ -199,6 +201,7 @@ impl Session {
     ) -> io::Result<bool>
     where
         REv: From<DumpConsensusStateRequest>
+            + From<ConsensusControlRequest>
             + From<ControlAnnouncement>
             + From<NetworkInfoRequest>
             + From<SetNodeStopRequest>
@@ -290,6 +293,42 @@ impl Session {
                             }
                         }
                     }
+                    Action::SetConsensusSync { enabled, era } => {
+                        let command = ConsensusControlCommand::SetSyncEnabled(enabled);
+                        let result = effect_builder
+                            .diagnostics_port_control_consensus(era.map(EraId::new), command)
+                            .await;
+
+                        match result {
+                            Ok(()) => {
+                                let msg = if enabled {
+                                    "enabled consensus sync"
+                                } else {
+                                    "disabled consensus sync"
+                                };
+                                self.send_outcome(writer, &Outcome::success(msg)).await?;
+                            }
+                            Err(err) => {
+                                self.send_outcome(writer, &Outcome::failed(err)).await?;
+                            }
+                        }
+                    }
+                    Action::ForceStartRound { round, era } => {
+                        let command = ConsensusControlCommand::ForceStartRound(round);
+                        let result = effect_builder
+                            .diagnostics_port_control_consensus(era.map(EraId::new), command)
+                            .await;
+
+                        match result {
+                            Ok(()) => {
+                                let msg = format!("force-started round {}", round);
+                                self.send_outcome(writer, &Outcome::success(msg)).await?;
+                            }
+                            Err(err) => {
+                                self.send_outcome(writer, &Outcome::failed(err)).await?;
+                            }
+                        }
+                    }
                     Action::DumpQueues => {
                         // Note: The preferable approach would be to use a tempfile instead of a
                         //       named one in a temporary directory, and return it through the
@@ -475,6 +514,7 @@ async fn handler<REv>(
 ) -> io::Result<()>
 where
     REv: From<DumpConsensusStateRequest>
+        + From<ConsensusControlRequest>
         + From<ControlAnnouncement>
         + From<NetworkInfoRequest>
         + From<SetNodeStopRequest>
@@ -519,6 +559,7 @@ pub(super) async fn server<REv>(
     mut shutdown_receiver: watch::Receiver<()>,
 ) where
     REv: From<DumpConsensusStateRequest>
+        + From<ConsensusControlRequest>
         + From<ControlAnnouncement>
         + From<NetworkInfoRequest>
         + From<SetNodeStopRequest>
@@ -600,7 +641,7 @@ mod tests {
         },
         effect::{
             announcements::ControlAnnouncement,
-            diagnostics_port::DumpConsensusStateRequest,
+            diagnostics_port::{ConsensusControlRequest, DumpConsensusStateRequest},
             requests::{NetworkInfoRequest, SetNodeStopRequest},
             EffectBuilder, EffectExt, Effects,
         },
@@ -656,6 +697,8 @@ mod tests {
         #[from]
         DumpConsensusStateRequest(DumpConsensusStateRequest),
         #[from]
+        ConsensusControlRequest(ConsensusControlRequest),
+        #[from]
         ControlAnnouncement(ControlAnnouncement),
         #[from]
         NetworkInfoRequest(NetworkInfoRequest),
@@ -705,6 +748,7 @@ mod tests {
                         .handle_event(effect_builder, rng, event),
                 ),
                 Event::DumpConsensusStateRequest(_)
+                | Event::ConsensusControlRequest(_)
                 | Event::SetNodeStopRequest(_)
                 | Event::ControlAnnouncement(_)
                 | Event::NetworkInfoRequest(_) => {
@@ -163,7 +163,9 @@ use announcements::{
     MetaBlockAnnouncement, PeerBehaviorAnnouncement, QueueDumpFormat, UnexecutedBlockAnnouncement,
     UpgradeWatcherAnnouncement,
 };
-use diagnostics_port::DumpConsensusStateRequest;
+use diagnostics_port::{
+    ConsensusControlCommand, ConsensusControlRequest, DumpConsensusStateRequest,
+};
 use requests::{
     AcceptDeployRequest, BeginGossipRequest, BlockAccumulatorRequest, BlockSynchronizerRequest,
     BlockValidationRequest, ChainspecRawBytesRequest, ConsensusRequest, ContractRuntimeRequest,
@@ -2084,6 +2086,26 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Apply an operator's control command to the consensus instance of a specific era.
+    pub(crate) async fn diagnostics_port_control_consensus(
+        self,
+        era_id: Option<EraId>,
+        command: ConsensusControlCommand,
+    ) -> Result<(), Cow<'static, str>>
+    where
+        REv: From<ConsensusControlRequest>,
+    {
+        self.make_request(
+            |responder| ConsensusControlRequest {
+                era_id,
+                command,
+                responder,
+            },
+            QueueKind::Control,
+        )
+        .await
+    }
+
     /// Dump the event queue contents to the diagnostics port, using the given serializer.
     pub(crate) async fn diagnostics_port_dump_queue(self, dump_format: QueueDumpFormat)
     where
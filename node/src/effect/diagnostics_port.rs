@@ -58,3 +58,57 @@ impl Debug for DumpConsensusStateRequest {
             .finish_non_exhaustive()
     }
 }
+
+/// A command changing the behavior of a consensus instance, issued by an operator.
+#[derive(Clone, Copy, DataSize, Debug, Serialize)]
+pub(crate) enum ConsensusControlCommand {
+    /// Enable or disable syncing the protocol state with random peers.
+    SetSyncEnabled(bool),
+    /// Make the given round the current one right away.
+    ForceStartRound(u32),
+}
+
+impl Display for ConsensusControlCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConsensusControlCommand::SetSyncEnabled(true) => f.write_str("enable sync"),
+            ConsensusControlCommand::SetSyncEnabled(false) => f.write_str("disable sync"),
+            ConsensusControlCommand::ForceStartRound(round_id) => {
+                write!(f, "force-start round {}", round_id)
+            }
+        }
+    }
+}
+
+/// A request to apply a control command to the consensus instance of a specific era.
+#[derive(DataSize, Serialize)]
+pub(crate) struct ConsensusControlRequest {
+    /// Era to apply the command to.
+    ///
+    /// If not given, use active era.
+    pub(crate) era_id: Option<EraId>,
+    /// The command to apply.
+    pub(crate) command: ConsensusControlCommand,
+    /// Responder to send the result into.
+    pub(crate) responder: Responder<Result<(), Cow<'static, str>>>,
+}
+
+impl Display for ConsensusControlRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} for ", self.command)?;
+        if let Some(ref era_id) = self.era_id {
+            Display::fmt(era_id, f)
+        } else {
+            f.write_str("latest era")
+        }
+    }
+}
+
+impl Debug for ConsensusControlRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConsensusControlRequest")
+            .field("era_id", &self.era_id)
+            .field("command", &self.command)
+            .finish_non_exhaustive()
+    }
+}
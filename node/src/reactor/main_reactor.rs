@@ -338,6 +338,10 @@ impl reactor::Reactor for MainReactor {
                 MainEvent::Consensus,
                 self.consensus.handle_event(effect_builder, rng, req.into()),
             ),
+            MainEvent::ConsensusControlRequest(req) => reactor::wrap_effects(
+                MainEvent::Consensus,
+                self.consensus.handle_event(effect_builder, rng, req.into()),
+            ),
 
             // NETWORK CONNECTION AND ORIENTATION
             MainEvent::Network(event) => reactor::wrap_effects(
@@ -26,7 +26,7 @@ use crate::{
             FetchedNewFinalitySignatureAnnouncement, GossiperAnnouncement, MetaBlockAnnouncement,
             PeerBehaviorAnnouncement, UnexecutedBlockAnnouncement, UpgradeWatcherAnnouncement,
         },
-        diagnostics_port::DumpConsensusStateRequest,
+        diagnostics_port::{ConsensusControlRequest, DumpConsensusStateRequest},
         incoming::{
             ConsensusDemand, ConsensusMessageIncoming, FinalitySignatureIncoming, GossiperIncoming,
             NetRequestIncoming, NetResponseIncoming, TrieDemand, TrieRequestIncoming,
@@ -91,6 +91,8 @@ pub(crate) enum MainEvent {
     #[from]
     DumpConsensusStateRequest(DumpConsensusStateRequest),
     #[from]
+    ConsensusControlRequest(ConsensusControlRequest),
+    #[from]
     Network(network::Event<Message>),
     #[from]
     NetworkRequest(#[serde(skip_serializing)] NetworkRequest<Message>),
@@ -314,6 +316,7 @@ impl ReactorEvent for MainEvent {
             MainEvent::StorageRequest(_) => "StorageRequest",
             MainEvent::MarkBlockCompletedRequest(_) => "MarkBlockCompletedRequest",
             MainEvent::DumpConsensusStateRequest(_) => "DumpConsensusStateRequest",
+            MainEvent::ConsensusControlRequest(_) => "ConsensusControlRequest",
             MainEvent::ControlAnnouncement(_) => "ControlAnnouncement",
             MainEvent::FatalAnnouncement(_) => "FatalAnnouncement",
             MainEvent::DeployAcceptorAnnouncement(_) => "DeployAcceptorAnnouncement",
@@ -486,6 +489,9 @@ impl Display for MainEvent {
             MainEvent::DumpConsensusStateRequest(req) => {
                 write!(f, "dump consensus state: {}", req)
             }
+            MainEvent::ConsensusControlRequest(req) => {
+                write!(f, "consensus control: {}", req)
+            }
             MainEvent::DeployAcceptorAnnouncement(ann) => {
                 write!(f, "deploy acceptor announcement: {}", ann)
             }
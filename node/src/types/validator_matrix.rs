@@ -11,7 +11,7 @@ use itertools::Itertools;
 use num_rational::Ratio;
 use serde::Serialize;
 use static_assertions::const_assert;
-use tracing::info;
+use tracing::{info, warn};
 
 use casper_types::{EraId, PublicKey, SecretKey, U512};
 
@@ -199,18 +199,31 @@ impl ValidatorMatrix {
     }
 
     pub(crate) fn validator_weights(&self, era_id: EraId) -> Option<EraValidatorWeights> {
-        if let (true, Some(chainspec_validators)) = (
-            era_id == self.chainspec_activation_era,
-            self.chainspec_validators.as_ref(),
-        ) {
-            Some(EraValidatorWeights::new(
-                era_id,
-                (**chainspec_validators).clone(),
-                self.finality_threshold_fraction,
-            ))
-        } else {
-            self.read_inner().get(&era_id).cloned()
+        self.chainspec_validator_weights(era_id)
+            .or_else(|| self.read_inner().get(&era_id).cloned())
+    }
+
+    /// Returns the validator weights from the chainspec if `era_id` is the activation era and the
+    /// chainspec specifies validators. These take precedence over the registered weights.
+    fn chainspec_validator_weights(&self, era_id: EraId) -> Option<EraValidatorWeights> {
+        if era_id != self.chainspec_activation_era {
+            return None;
         }
+        let chainspec_validators = self.chainspec_validators.as_ref()?;
+        Some(EraValidatorWeights::new(
+            era_id,
+            (**chainspec_validators).clone(),
+            self.finality_threshold_fraction,
+        ))
+    }
+
+    /// Returns the validator weights of the era the signature claims to belong to, i.e. the ones
+    /// it must be verified against, or `None` if that era is not known.
+    pub(crate) fn weights_for_signature(
+        &self,
+        signature: &FinalitySignature,
+    ) -> Option<EraValidatorWeights> {
+        self.validator_weights(signature.era_id)
     }
 
     pub(crate) fn fault_tolerance_threshold(&self) -> Ratio<u64> {
@@ -410,20 +423,46 @@ impl EraValidatorWeights {
         }
         SignatureWeight::Insufficient
     }
+
+    /// Returns the signature weight of the given finality signatures. Signatures that claim to
+    /// be from a different era are ignored, even if the signer is a validator in this one.
+    pub(crate) fn finality_signature_weight<'a>(
+        &self,
+        finality_signatures: impl Iterator<Item = &'a FinalitySignature>,
+    ) -> SignatureWeight {
+        let era_id = self.era_id;
+        self.signature_weight(finality_signatures.filter_map(|finality_signature| {
+            if finality_signature.era_id == era_id {
+                Some(&finality_signature.public_key)
+            } else {
+                warn!(
+                    %era_id,
+                    signature_era_id = %finality_signature.era_id,
+                    public_key = %finality_signature.public_key,
+                    "ignoring finality signature from a different era"
+                );
+                None
+            }
+        }))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use std::iter;
 
-    use casper_types::EraId;
+    use casper_types::{EraId, PublicKey, SecretKey};
     use num_rational::Ratio;
 
     use crate::{
         components::consensus::tests::utils::{
-            ALICE_PUBLIC_KEY, ALICE_SECRET_KEY, BOB_PUBLIC_KEY, CAROL_PUBLIC_KEY,
+            ALICE_PUBLIC_KEY, ALICE_SECRET_KEY, BOB_PUBLIC_KEY, BOB_SECRET_KEY, CAROL_PUBLIC_KEY,
+            CAROL_SECRET_KEY,
+        },
+        types::{
+            validator_matrix::MAX_VALIDATOR_MATRIX_ENTRIES, BlockHash, FinalitySignature,
+            SignatureWeight,
         },
-        types::{validator_matrix::MAX_VALIDATOR_MATRIX_ENTRIES, SignatureWeight},
     };
 
     use super::{EraValidatorWeights, ValidatorMatrix};
@@ -536,6 +575,70 @@ mod tests {
         );
     }
 
+    #[test]
+    fn finality_signature_weight_ignores_other_eras() {
+        let mut rng = crate::new_rng();
+        let era_id = EraId::new(5);
+        let weights = EraValidatorWeights::new(
+            era_id,
+            [
+                (ALICE_PUBLIC_KEY.clone(), 100.into()),
+                (BOB_PUBLIC_KEY.clone(), 100.into()),
+                (CAROL_PUBLIC_KEY.clone(), 100.into()),
+            ]
+            .into(),
+            Ratio::new(1, 3),
+        );
+        let block_hash = BlockHash::random(&mut rng);
+        let sign = |era_id: EraId, secret_key: &SecretKey| {
+            FinalitySignature::create(block_hash, era_id, secret_key, PublicKey::from(secret_key))
+        };
+        let alice_sig = sign(era_id, &ALICE_SECRET_KEY);
+        let bob_sig = sign(era_id, &BOB_SECRET_KEY);
+        let carol_sig = sign(era_id, &CAROL_SECRET_KEY);
+        let carol_other_era_sig = sign(era_id.predecessor().unwrap(), &CAROL_SECRET_KEY);
+
+        assert_eq!(
+            weights.finality_signature_weight([&alice_sig, &bob_sig, &carol_sig].into_iter()),
+            SignatureWeight::Strict
+        );
+        assert_eq!(
+            weights.finality_signature_weight(
+                [&alice_sig, &bob_sig, &carol_other_era_sig].into_iter()
+            ),
+            SignatureWeight::Weak
+        );
+        assert_eq!(
+            weights.finality_signature_weight(iter::once(&carol_other_era_sig)),
+            SignatureWeight::Insufficient
+        );
+    }
+
+    #[test]
+    fn weights_for_signature_uses_signature_era() {
+        let mut rng = crate::new_rng();
+        let validator_matrix = ValidatorMatrix::new_with_validator(ALICE_SECRET_KEY.clone());
+        let block_hash = BlockHash::random(&mut rng);
+        let sign = |era_id: EraId| {
+            FinalitySignature::create(
+                block_hash,
+                era_id,
+                &ALICE_SECRET_KEY,
+                ALICE_PUBLIC_KEY.clone(),
+            )
+        };
+
+        let weights = validator_matrix
+            .weights_for_signature(&sign(EraId::new(0)))
+            .expect("era 0 should be known");
+        assert_eq!(EraId::new(0), weights.era_id());
+        assert!(weights.is_validator(&ALICE_PUBLIC_KEY));
+
+        assert!(validator_matrix
+            .weights_for_signature(&sign(EraId::new(1)))
+            .is_none());
+    }
+
     #[test]
     fn register_validator_weights_pruning() {
         // Create a validator matrix and saturate it with entries.
@@ -52,8 +52,19 @@ pub(crate) struct EraDump {
     /// The validator weights.
     pub validators: BTreeMap<PublicKey, U512>,
 
-    /// The state of the highway instance associated with the era.
-    pub highway_state: State<ClContext>,
+    /// The state of the highway instance associated with the era, if it runs Highway.
+    pub highway_state: Option<State<ClContext>>,
+}
+
+impl EraDump {
+    /// Returns the state of the highway instance.
+    ///
+    /// Panics if the era doesn't run Highway.
+    fn highway_state(&self) -> &State<ClContext> {
+        self.highway_state
+            .as_ref()
+            .expect("the era dump doesn't contain a Highway state")
+    }
 }
 
 fn main() {
@@ -74,7 +85,7 @@ fn main() {
     let validators =
         validators::<ClContext>(&dump.faulty, &dump.cannot_propose, dump.validators.clone());
 
-    print_faults(&validators, &dump.highway_state);
+    print_faults(&validators, dump.highway_state());
 
     print_skipped_rounds(&validators, &dump, args.verbose);
 
@@ -97,12 +108,12 @@ fn print_faults(validators: &Validators<PublicKey>, state: &State<ClContext>) {
 const TOP_TO_PRINT: usize = 10;
 
 fn round_num(dump: &EraDump, round_id: Timestamp) -> u64 {
-    let min_round_length = dump.highway_state.params().min_round_length();
+    let min_round_length = dump.highway_state().params().min_round_length();
     (round_id.millis() - dump.start_time.millis()) / min_round_length.millis()
 }
 
 fn print_skipped_rounds(validators: &Validators<PublicKey>, dump: &EraDump, verbose: bool) {
-    let state = &dump.highway_state;
+    let state = dump.highway_state();
     let highest_block = state.fork_choice(state.panorama()).unwrap();
     let all_blocks = std::iter::once(highest_block).chain(state.ancestor_hashes(highest_block));
     let mut skipped_rounds = vec![vec![]; validators.len()];
@@ -158,7 +169,7 @@ fn print_skipped_rounds(validators: &Validators<PublicKey>, dump: &EraDump, verb
 }
 
 fn print_lowest_quorum_participation(validators: &Validators<PublicKey>, dump: &EraDump) {
-    let state = &dump.highway_state;
+    let state = dump.highway_state();
     let highest_block = state.fork_choice(state.panorama()).unwrap();
     let mut quora_sum = vec![0.0; validators.len()];
     let mut num_rounds = 0;